@@ -2,6 +2,8 @@
 
 #[ink::contract]
 mod dns {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
     use ink::storage::Mapping;
     use scale::{Decode, Encode};
 
@@ -11,6 +13,19 @@ mod dns {
         name_to_address: Mapping<Hash, AccountId>,
         name_to_owner: Mapping<Hash, AccountId>,
         default_address: AccountId,
+        // document a name resolve to, names are the blake2 hash of e.g. `q3-report.acme`
+        name_to_document: Mapping<Hash, DocumentRef>,
+    }
+
+    // a document stored in a `DocumentManagement` contract
+    #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct DocumentRef {
+        pub contract: AccountId,
+        pub document_id: u32,
     }
 
     #[derive(Debug, Clone, Encode, Decode)]
@@ -20,6 +35,8 @@ mod dns {
         NotOwner,
         NonExist,
         NoDataFound,
+        NotDocumentOwner,
+        DocumentCallFailed,
     }
     #[ink(event)]
     pub struct Register {
@@ -48,6 +65,15 @@ mod dns {
         #[ink(topic)]
         new_address: AccountId,
     }
+    #[ink(event)]
+    pub struct SetDocument {
+        #[ink(topic)]
+        name: Hash,
+        from: AccountId,
+        #[ink(topic)]
+        contract: AccountId,
+        document_id: u32,
+    }
     impl Dns {
         #[ink(constructor)]
         pub fn new() -> Self {
@@ -55,6 +81,7 @@ mod dns {
                 name_to_address: Mapping::default(),
                 name_to_owner: Mapping::default(),
                 default_address: AccountId::from([0x00; 32]),
+                name_to_document: Mapping::default(),
             }
         }
         #[ink(message)]
@@ -115,6 +142,56 @@ mod dns {
         pub fn get_owner(&self, name: Hash) -> AccountId {
             self.name_to_owner.get(name).unwrap_or(self.default_address)
         }
+        // point the name to a document, registering the name first if nobody owns it.
+        // The caller has to own both the name and the document
+        #[ink(message)]
+        pub fn register_document(
+            &mut self,
+            name: Hash,
+            contract: AccountId,
+            document_id: u32,
+        ) -> DnsResult<()> {
+            let caller = self.env().caller();
+            if self.name_to_owner.contains(name) && !self.check_is_owner(&name, &caller) {
+                return Err(DnsError::NotOwner);
+            }
+            let owned = build_call::<DefaultEnvironment>()
+                .call(contract)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("document_owned_by")))
+                        .push_arg(caller)
+                        .push_arg(document_id),
+                )
+                .returns::<bool>()
+                .try_invoke();
+            match owned {
+                Ok(Ok(true)) => {}
+                Ok(Ok(false)) => return Err(DnsError::NotDocumentOwner),
+                _ => return Err(DnsError::DocumentCallFailed),
+            }
+            if !self.name_to_owner.contains(name) {
+                self.name_to_owner.insert(name, &caller);
+                self.env().emit_event(Register { from: caller, name });
+            }
+            self.name_to_document.insert(
+                name,
+                &DocumentRef {
+                    contract,
+                    document_id,
+                },
+            );
+            self.env().emit_event(SetDocument {
+                name,
+                from: caller,
+                contract,
+                document_id,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn get_document(&self, name: Hash) -> Option<DocumentRef> {
+            self.name_to_document.get(name)
+        }
         fn check_is_owner(&self, name: &Hash, addr: &AccountId) -> bool {
             match self.name_to_owner.get(name) {
                 Some(val) => val == *addr,
@@ -145,6 +222,21 @@ mod dns {
         /// We test a simple use case of our contract.
         #[ink::test]
         fn it_works() {}
+
+        /// We test that only the name owner can point a name to a document.
+        #[ink::test]
+        fn register_document_requires_name_owner() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut dns = Dns::new();
+            let name = Hash::from([0x01; 32]);
+            assert!(dns.register(name).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(matches!(
+                dns.register_document(name, accounts.django, 1),
+                Err(DnsError::NotOwner)
+            ));
+            assert_eq!(dns.get_document(name), None);
+        }
     }
 
     /// This is how you'd write end-to-end (E2E) or integration tests for ink! contracts.
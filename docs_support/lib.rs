@@ -1,10 +1,19 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
-use core::ops::Deref;
-
-use ink::env::hash::{Blake2x128, Blake2x256, HashOutput, Sha2x256};
+use ink::env::hash::{Blake2x256, HashOutput};
 use ink::primitives::Hash;
 
+pub mod storage_keys;
+
+// role granted by `DocumentAcounts`, also checked by the contracts that query it
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum Role {
+    Admin,
+    Speculator,
+}
+
 pub trait Builder {
     type OutputType;
     fn add_segment(self, input: &[u8]) -> Self;
@@ -17,18 +26,15 @@ pub struct HashBuilder {
 }
 impl Builder for HashBuilder {
     type OutputType = Hash;
+    // segments of any length are folded into the buffer, uuids mix in single bytes
     fn add_segment(mut self, input: &[u8]) -> Self {
-        let left = &self.buffer[..16];
-        let right = &input.as_ref()[16..];
-        let mut res = [0u8; 32];
-        res[16..].copy_from_slice(left);
-        res[..16].copy_from_slice(right);
+        let mut res = <Blake2x256 as HashOutput>::Type::default();
+        ink::env::hash_encoded::<Blake2x256, _>(&(self.buffer, input), &mut res);
         self.buffer = res;
         self
     }
     fn build(self) -> Self::OutputType {
         let mut output_hash = <Blake2x256 as HashOutput>::Type::default();
-        let input = &self.buffer;
         ink::env::hash_bytes::<Blake2x256>(&self.buffer, &mut output_hash);
         output_hash.into()
     }
@@ -41,12 +47,13 @@ mod buffer {
 
     #[ink::test]
     fn build_hash() {
-        let mut hash_builder = HashBuilder::default();
+        let hash_builder = HashBuilder::default();
         let input1: AccountId = [0x17; 32].into();
         let input2: AccountId = [0x11; 32].into();
         let res_hash = hash_builder
             .add_segment(input1.as_ref())
             .add_segment(input2.as_ref())
+            .add_segment(&[0x01])
             .build();
         assert_ne!(res_hash, [0u8; 32].into());
         println!("res_hash {:?}", res_hash);
@@ -0,0 +1,115 @@
+pub use ink::primitives::{Key, KeyComposer};
+
+// key of the `#[ink(storage)]` struct itself, every contract keep its root there. The
+// declared keys stay off it and a contract whose storage is written by delegate calls
+// keeps a root without packed fields, like the code it delegates to
+pub const ROOT_KEY: Key = 0;
+
+// true when a key appears more than once, const so it can run in a `const` assertion
+pub const fn has_duplicate_keys(keys: &[Key]) -> bool {
+    let mut i = 0;
+    while i < keys.len() {
+        let mut j = i + 1;
+        while j < keys.len() {
+            if keys[i] == keys[j] {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+// true when no key of `left` is used in `right`
+pub const fn keys_disjoint(left: &[Key], right: &[Key]) -> bool {
+    let mut i = 0;
+    while i < left.len() {
+        let mut j = 0;
+        while j < right.len() {
+            if left[i] == right[j] {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Declare the storage keys of a contract in one place. Every key become a `Key` const,
+/// `STORAGE_KEYS` list them all and the build fails if two of them are equal.
+///
+/// ```
+/// docs_support::storage_keys! {
+///     OWNER_KEY = 0xba8adf68;
+///     CONTENT_KEY = 0x9da24437;
+/// }
+/// assert_eq!(STORAGE_KEYS, &[OWNER_KEY, CONTENT_KEY]);
+/// ```
+#[macro_export]
+macro_rules! storage_keys {
+    ($($vis:vis $name:ident = $key:expr;)*) => {
+        $($vis const $name: $crate::storage_keys::Key = $key;)*
+        pub const STORAGE_KEYS: &[$crate::storage_keys::Key] = &[$($name),*];
+        const _: () = assert!(
+            !$crate::storage_keys::has_duplicate_keys(STORAGE_KEYS),
+            "duplicated storage key"
+        );
+        const _: () = assert!(
+            $crate::storage_keys::keys_disjoint(STORAGE_KEYS, &[$crate::storage_keys::ROOT_KEY]),
+            "storage key on the root key"
+        );
+    };
+}
+
+// keys of `DocumentAcounts`
+pub mod accounts {
+    crate::storage_keys! {
+        pub VERSION_KEY = 0x9e245fad;
+        pub IDENTITIES_KEY = 0x53cd0567;
+        pub ROLE_KEY = 0xa5c4ec7f;
+        pub DOMAIN_KEY = 0x2acfa878;
+        pub METADATA_KEY = 0x12896c2e;
+    }
+}
+
+// keys of `proxy_account::Proxy`
+pub mod proxy {
+    crate::storage_keys! {
+        pub CONTRACT_VERSION_KEY = 0x7672736e;
+        pub IDENTITIES_KEY = 0x6964656e;
+        pub DELEGATEE_VERSION_KEY = 0x646c7673;
+        // the key ink derived for the field before it got a manual one, deployed proxies
+        // keep their code hash there
+        pub DELEGATE_TO_KEY = super::KeyComposer::from_str("Proxy::delegate_to");
+        pub DEVELOPER_KEY = 0xa352c83f;
+    }
+}
+
+// the proxy delegate calls into `DocumentAcounts`, both contracts write the proxy storage
+const _: () = assert!(
+    keys_disjoint(accounts::STORAGE_KEYS, proxy::STORAGE_KEYS),
+    "proxy and accounts storage keys collide"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_duplicated_keys() {
+        assert!(!has_duplicate_keys(&[0x01, 0x02, 0x03]));
+        assert!(has_duplicate_keys(&[0x01, 0x02, 0x01]));
+        assert!(keys_disjoint(&[0x01], &[0x02, 0x03]));
+        assert!(!keys_disjoint(&[0x01, 0x03], &[0x02, 0x03]));
+        assert!(!has_duplicate_keys(accounts::STORAGE_KEYS));
+        assert!(!has_duplicate_keys(proxy::STORAGE_KEYS));
+        assert!(keys_disjoint(accounts::STORAGE_KEYS, &[ROOT_KEY]));
+        assert!(keys_disjoint(proxy::STORAGE_KEYS, &[ROOT_KEY]));
+        assert_eq!(
+            proxy::DELEGATE_TO_KEY,
+            KeyComposer::compute_key("Proxy", "", "delegate_to").unwrap()
+        );
+    }
+}
@@ -1,25 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 #[ink::contract]
 pub mod document_acounts {
-    use ink::{
-        env::{
-            hash::{Blake2x256, CryptoHash},
-            ContractEnv,
-        },
-        prelude::vec,
-        primitives::{self, Key},
-        storage::{traits::ManualKey, Lazy, Mapping, StorageVec},
-    };
+    use ink::storage::{traits::ManualKey, Lazy, Mapping};
 
-    use docs_support::HashBuilder;
-
-    // using random hex generator :)
-    const VERSION_KEY: Key = 0x9e245fad;
-    const IDENTITIES_KEY: Key = 0x53cd0567;
-    const PERMISSON_KEY: Key = 0x71e6551d;
-    const ROLE_KEY: Key = 0xa5c4ec7f;
-    const DOMAIN_KEY: Key = 0x2acfa878;
-    const METADATA_KEY: Key = 0x12896c2e;
+    use docs_support::storage_keys::accounts::*;
+    use docs_support::{Builder, HashBuilder};
+    pub use docs_support::Role;
 
     type AccountResult<T> = Result<T, AccountError>;
     #[ink(storage)]
@@ -32,14 +18,6 @@ pub mod document_acounts {
         metadata: Mapping<AccountId, Hash, ManualKey<METADATA_KEY>>,
     }
 
-    #[derive(Debug, PartialEq, Eq, Clone)]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    pub enum Role {
-        Admin,
-        Speculator,
-    }
-
     #[derive(Debug, PartialEq, Eq, Clone)]
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
     #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
@@ -47,6 +25,7 @@ pub mod document_acounts {
         DupilcatedUUID,
         AccountAlreadyHaveUUID,
         Unauthorized,
+        NoDataFound,
     }
 
     #[derive(Debug, PartialEq, Eq, Clone)]
@@ -115,7 +94,7 @@ pub mod document_acounts {
                 from: Some(caller),
                 to: None,
                 event_type: EventType::VersionUpdated {
-                    old_version: old_version,
+                    old_version,
                     new_version: init_version,
                 },
             });
@@ -132,14 +111,14 @@ pub mod document_acounts {
                 );
                 return Err(AccountError::Unauthorized);
             }
-            if let Some(caller_role) = self.role.get(&caller) {
+            if let Some(caller_role) = self.role.get(caller) {
                 match caller_role {
                     Role::Admin => {
                         self.role.insert(to, &role);
                         self.env().emit_event(Event {
                             from: Some(caller),
                             to: Some(to),
-                            event_type: EventType::RoleGranted { role: role },
+                            event_type: EventType::RoleGranted { role },
                         });
                     }
                     _ => return Err(AccountError::Unauthorized),
@@ -158,7 +137,7 @@ pub mod document_acounts {
                 );
                 return Err(AccountError::Unauthorized);
             }
-            if let Some(caller_role) = self.role.get(&caller) {
+            if let Some(caller_role) = self.role.get(caller) {
                 match caller_role {
                     Role::Admin => {
                         let previous_role = self
@@ -184,14 +163,23 @@ pub mod document_acounts {
             match self.role.get(account_id) {
                 Some(role) => {
                     ink::env::debug_println!("Role of account input is '{:?}'", role);
-                    return Ok(role);
+                    Ok(role)
                 }
                 None => {
                     ink::env::debug_println!("No Role exist for this account");
-                    return Err(AccountError::NoDataFound);
+                    Err(AccountError::NoDataFound)
                 }
             }
         }
+        // queried by other contracts to check whether an account registered an identity
+        #[ink(message)]
+        pub fn account_has_identity(&self, account_id: AccountId) -> bool {
+            self.identities.contains(account_id)
+        }
+        #[ink(message)]
+        pub fn account_has_role(&self, account_id: AccountId, role: Role) -> bool {
+            self.role.get(account_id) == Some(role)
+        }
 
         fn account_has_uuid(&self, account_id: &AccountId) -> bool {
             match self.identities.get(account_id) {
@@ -200,13 +188,13 @@ pub mod document_acounts {
                         "Accound Error:UUID for this account already exist {:?}",
                         AccountError::AccountAlreadyHaveUUID
                     );
-                    return true;
+                    true
                 }
-                None => return false,
+                None => false,
             }
         }
         fn generate_uuid(&self, input: &[u8]) -> Hash {
-            let mut uuid_builder = HashBuilder::default();
+            let uuid_builder = HashBuilder::default();
             let block_height = &[self.env().block_number() as u8];
             let time_stamp = &[self.env().block_timestamp() as u8];
             uuid_builder
@@ -226,17 +214,5 @@ pub mod document_acounts {
             }
             true
         }
-        fn get_caller_role(&self, caller: &AccountId) -> Option<Role> {
-            match self.role.get(caller) {
-                Some(role) => {
-                    ink::env::debug_println!("caller '{:?}' has role '{:?}'", caller, role);
-                    return Some(role);
-                }
-                None => {
-                    ink::env::debug_println!("caller '{:?}' has No role", caller);
-                    return None;
-                }
-            }
-        }
     }
 }
@@ -6,17 +6,22 @@ pub mod proxy_account {
     use ink::env::{CallFlags, DefaultEnvironment};
     use ink::scale::{Decode, Encode};
     use ink::storage::{traits::ManualKey, Lazy, Mapping};
+    // the proxy storage also hold the `DocumentAcounts` entries written by delegate calls,
+    // these keys are checked against the accounts keys in `docs_support`
+    use docs_support::storage_keys::proxy::*;
     use scale_info::TypeInfo;
 
     type ProxyResult<T> = Result<T, ProxyError>;
-    type UUID = u32;
+    type Uuid = u32;
+    // the root is shared with the `DocumentAcounts` root the delegate calls read and write,
+    // it has no packed field so both encode the same
     #[ink(storage)]
     pub struct Proxy {
-        developer: AccountId,
-        contract_version: Lazy<u32, ManualKey<0x7672736e>>,
-        identities: Mapping<AccountId, UUID, ManualKey<0x6964656e>>,
-        delegatee_version: Lazy<u32, ManualKey<0x646c7673>>,
-        delegate_to: Lazy<Hash>,
+        developer: Lazy<AccountId, ManualKey<DEVELOPER_KEY>>,
+        contract_version: Lazy<u32, ManualKey<CONTRACT_VERSION_KEY>>,
+        identities: Mapping<AccountId, Uuid, ManualKey<IDENTITIES_KEY>>,
+        delegatee_version: Lazy<u32, ManualKey<DELEGATEE_VERSION_KEY>>,
+        delegate_to: Lazy<Hash, ManualKey<DELEGATE_TO_KEY>>,
     }
     #[derive(Debug, Clone, Encode, Decode, PartialEq, Eq, TypeInfo)]
     #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
@@ -40,8 +45,9 @@ pub mod proxy_account {
     }
     impl Proxy {
         #[ink(constructor)]
-        pub fn new(init_value: i32, hash: Hash) -> Self {
-            let developer = Self::env().caller();
+        pub fn new(_init_value: i32, hash: Hash) -> Self {
+            let mut developer = Lazy::new();
+            developer.set(&Self::env().caller());
             let mut delegate_to = Lazy::new();
             delegate_to.set(&hash);
             Self::env().lock_delegate_dependency(&hash);
@@ -74,7 +80,7 @@ pub mod proxy_account {
             let gas_before = self.env().gas_left();
             ink::env::debug_print!("Gas before delegate call {}", gas_before);
             let selector = ink::selector_bytes!("document_account_new");
-            build_call::<DefaultEnvironment>()
+            let _ = build_call::<DefaultEnvironment>()
                 .delegate(self.get_delegatee_hash())
                 .exec_input(ExecutionInput::new(Selector::new(selector)))
                 .returns::<()>()
@@ -87,7 +93,7 @@ pub mod proxy_account {
             let gas_before = self.env().gas_left();
             ink::env::debug_print!("Gas before delegate call {}", gas_before);
             let selector = ink::selector_bytes!("document_account_version");
-            build_call::<DefaultEnvironment>()
+            let _ = build_call::<DefaultEnvironment>()
                 .delegate(self.get_delegatee_hash())
                 .call_flags(CallFlags::TAIL_CALL)
                 .exec_input(ExecutionInput::new(Selector::new(selector)))
@@ -144,7 +150,7 @@ pub mod proxy_account {
         }
         fn check_is_developer(&self) -> bool {
             let caller = self.env().caller();
-            return caller == self.developer;
+            Some(caller) == self.developer.get()
         }
     }
 
@@ -157,6 +163,22 @@ pub mod proxy_account {
 
         #[ink::test]
         fn it_works() {}
+
+        #[ink::test]
+        fn root_holds_no_packed_field() {
+            let mut developer = Lazy::new();
+            developer.set(&AccountId::from([0x01; 32]));
+            let proxy = Proxy {
+                developer,
+                contract_version: Lazy::default(),
+                identities: Mapping::default(),
+                delegatee_version: Lazy::default(),
+                delegate_to: Lazy::default(),
+            };
+            let mut root = Vec::new();
+            ink::storage::traits::Storable::encode(&proxy, &mut root);
+            assert!(root.is_empty());
+        }
     }
 
     /// This is how you'd write end-to-end (E2E) or integration tests for ink! contracts.
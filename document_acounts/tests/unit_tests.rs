@@ -4,7 +4,7 @@
 #[cfg(test)]
 mod tests {
     /// Imports all the definitions from the outer scope so we can use them here.
-    use document_acounts::document_acounts::*;
+    use document_store_contract::document_acounts::*;
 
     /// We test if the default constructor does its job.
     #[ink::test]
@@ -13,4 +13,20 @@ mod tests {
     /// We test a simple use case of our contract.
     #[ink::test]
     fn it_works() {}
+
+    /// We test the identity and role queries used by other contracts.
+    #[ink::test]
+    fn identity_queries_work() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut contract = DocumentAcounts::new();
+        assert!(!contract.account_has_identity(accounts.alice));
+        assert_eq!(contract.account_new(), Ok(()));
+        assert!(contract.account_has_identity(accounts.alice));
+        assert!(!contract.account_has_identity(accounts.bob));
+        assert!(!contract.account_has_role(accounts.alice, Role::Admin));
+        assert_eq!(
+            contract.get_role(accounts.alice),
+            Err(AccountError::NoDataFound)
+        );
+    }
 }
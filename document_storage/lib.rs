@@ -2,18 +2,13 @@
 
 #[ink::contract]
 pub mod document_management {
-    use ink::env::call;
-    use ink::primitives::{self, Key};
+    pub use docs_support::Role;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::hash::Blake2x256;
+    use ink::prelude::vec::Vec;
     use ink::scale::{Decode, Encode};
     use ink::storage::traits::ManualKey;
-    use ink::storage::Mapping;
-    use ink::{
-        env::caller,
-        prelude::{
-            collections::{BinaryHeap, HashMap, HashSet},
-            vec,
-        },
-    };
+    use ink::storage::{Lazy, Mapping};
 
     // documentID represent ERC721 - non fungiable token
     pub type DocumentId = u32;
@@ -21,30 +16,272 @@ pub mod document_management {
     //helper type
     pub type DocumentResult<T> = Result<T, DocumentError>;
     pub type IPFSaddr = Hash;
-    //storage key
-    const OWNER_KEY: Key = 0xba8adf68;
-    const CONTENT_KEY: Key = 0x9da24437;
-    const METADATA_KEY: Key = 0x934025b6;
-    const LOCATION_KEY: Key = 0x9474161a;
-    const DOCUMENT_COUNTER_KEY: Key = 0x2434e302;
-    const OPRATOR_APPROVAL_KEY: Key = 0x613e74f2;
-    const DOCUMENT_APPROVAL_KEY: Key = 0xd9b60c53;
+    // a delivery is found by its document and its id within the document
+    pub type DeliveryRef = (DocumentId, u32);
+    // a link is found by its source, its target and its kind
+    pub type LinkRef = (DocumentId, DocumentId, LinkKind);
+    //storage key, a duplicated key fails the build
+    docs_support::storage_keys! {
+        OWNER_KEY = 0xba8adf68;
+        CONTENT_KEY = 0x9da24437;
+        METADATA_KEY = 0x934025b6;
+        LOCATION_KEY = 0x9474161a;
+        DOCUMENT_COUNTER_KEY = 0x2434e302;
+        OPRATOR_APPROVAL_KEY = 0x613e74f2;
+        DOCUMENT_APPROVAL_KEY = 0xd9b60c53;
+        ATTESTATION_KEY = 0x165a65d7;
+        ATTESTATION_COUNTER_KEY = 0x8c60d076;
+        ATTESTED_SIGNER_KEY = 0x795ef110;
+        PERMIT_NONCE_KEY = 0x4615ab0a;
+        LICENSE_TERMS_KEY = 0xc82b14f4;
+        LICENSE_KEY = 0x8eea2db0;
+        LISTING_KEY = 0x0cfe3f03;
+        OFFER_KEY = 0x33b42bef;
+        ROYALTY_KEY = 0x33a79e9c;
+        DOCUMENT_USER_KEY = 0x7997de17;
+        KEY_ENVELOPE_KEY = 0x2ce7945e;
+        ENVELOPE_RECIPIENTS_KEY = 0xe6d7d674;
+        KEY_VERSION_KEY = 0x145834f2;
+        OUTGOING_LINK_KEY = 0x63950b7e;
+        OUTGOING_LINK_COUNTER_KEY = 0xd17a2d9e;
+        INCOMING_LINK_KEY = 0x6a83fa6e;
+        INCOMING_LINK_COUNTER_KEY = 0xae11b847;
+        LINKED_KEY = 0xf30bb8c4;
+        SUPERSEDED_BY_KEY = 0xdffcc1af;
+        ADMIN_KEY = 0x792892c5;
+        RETENTION_POLICY_KEY = 0xf6dceb57;
+        DOCUMENT_RETENTION_KEY = 0x0cbc4ded;
+        DEPOSIT_PER_ENTRY_KEY = 0x28761472;
+        DOCUMENT_DEPOSIT_KEY = 0xa5919537;
+        DEFAULT_QUOTA_KEY = 0x7fa99c46;
+        ACCOUNT_QUOTA_KEY = 0xa31d8c02;
+        PAUSED_KEY = 0xdfc5c95a;
+        UPGRADE_AUTHORITY_KEY = 0x53e34186;
+        UPGRADE_DELAY_KEY = 0xfed431df;
+        PENDING_UPGRADE_KEY = 0xabea025c;
+        PENDING_UPGRADE_DELAY_KEY = 0xdd5fa5a0;
+        LAYOUT_VERSION_KEY = 0xbff53dd4;
+        MIGRATION_KEY = 0xd435a698;
+        DOCUMENT_INDEX_KEY = 0x078d032c;
+        DOCUMENT_INDEX_POSITION_KEY = 0xb230dd30;
+        DOCUMENT_INDEX_LEN_KEY = 0x054157f1;
+        ACCESS_REQUIREMENT_KEY = 0x84bae1a5;
+        DOCUMENT_RECORD_KEY = 0xe4f2b1b1;
+        DOCUMENT_AUTHORS_KEY = 0x8e9e1a1d;
+        PENDING_AUTHORS_KEY = 0x061f6eca;
+        AUTHORED_DOCUMENT_KEY = 0x518ab266;
+        AUTHORED_COUNTER_KEY = 0xde8d186e;
+        AUTHORED_POSITION_KEY = 0x620eafbd;
+        CO_OWNERSHIP_KEY = 0x4d126688;
+        PROPOSAL_KEY = 0x93734c55;
+        PROPOSAL_COUNTER_KEY = 0x7f571273;
+        OWNED_DOCUMENT_KEY = 0x8f71afb4;
+        OWNED_POSITION_KEY = 0xfd6c93a9;
+        OWNED_INDEX_LEN_KEY = 0x5e24a82e;
+        RECOVERY_CONFIG_KEY = 0xe2c1eeb6;
+        RECOVERY_KEY = 0x0fb49d35;
+        AUDITED_ACCESS_KEY = 0x6102a0b1;
+        ACCESS_LOG_KEY = 0x3c591d05;
+        ACCESS_LOG_LEN_KEY = 0xb99d5ae5;
+        DELIVERY_KEY = 0x3a620c05;
+        DELIVERY_COUNTER_KEY = 0xf9ba76c8;
+        PENDING_DELIVERY_KEY = 0x84df6fa6;
+        RECEIVED_DELIVERY_KEY = 0x3690c2dc;
+        RECEIVED_COUNTER_KEY = 0x89d66a44;
+        TEMPLATE_KEY = 0xbb9541bb;
+        TEMPLATE_VERSION_KEY = 0x58b831ac;
+        TEMPLATE_COUNTER_KEY = 0x4aeb6e0c;
+        DOCUMENT_TEMPLATE_KEY = 0xb2f7b26e;
+        NEXT_DOCUMENT_ID_KEY = 0x6d6da874;
+        LICENSEE_KEY = 0x7c6933ef;
+        LICENSEE_COUNTER_KEY = 0xb7becbca;
+        SUPERSEDE_REQUEST_KEY = 0xa9337b91;
+        RECEIVED_POSITION_KEY = 0xc4c96ab1;
+        OFFER_BUYER_KEY = 0xecc2fb32;
+        OFFER_COUNTER_KEY = 0x04bba4d7;
+        OFFER_POSITION_KEY = 0xe322e4be;
+        BACKED_TEMPLATE_KEY = 0x089c59cb;
+        CO_OWNED_DOCUMENT_KEY = 0x6ded0bf1;
+        CO_OWNED_POSITION_KEY = 0x6eed8d0f;
+        CO_OWNED_INDEX_LEN_KEY = 0x3b115039;
+    }
+
+    // domain tag of the permit payload, keeps permit signatures from being replayed as
+    // signatures for anything else
+    const PERMIT_DOMAIN: &[u8] = b"DocumentManagement::permit";
+
+    // royalties are expressed in basis points of the sale price
+    const ROYALTY_DENOMINATOR: u16 = 10_000;
+
+    // bounds of the encrypted key envelopes stored per document
+    const MAX_ENVELOPE_SIZE: usize = 512;
+    const MAX_ENVELOPE_RECIPIENTS: usize = 32;
+
+    // confirmed and pending authors a document can name
+    const MAX_AUTHORS: usize = 16;
+
+    // accounts sharing the ownership of a document
+    const MAX_CO_OWNERS: usize = 16;
+
+    // guardians an account can name to recover its documents
+    const MAX_GUARDIANS: usize = 16;
+
+    // number of supersedes links walked when checking a new link for cycles
+    const MAX_LINEAGE_DEPTH: u32 = 64;
+
+    // blocks a proposed code hash waits before it can be applied, about a day of 6s blocks
+    const DEFAULT_UPGRADE_DELAY: BlockNumber = 14_400;
+
+    // storage layout written by this code, bumped together with a new migration step.
+    // Instances deployed before the layout got versioned read as version 0
+    const CURRENT_LAYOUT_VERSION: u32 = 4;
+
+    // documents processed by a single `migrate` call
+    const MIGRATION_CHUNK_SIZE: u32 = 50;
+
+    // upper bound of entries returned by a paged query
+    const MAX_PAGE_SIZE: u32 = 50;
 
     #[ink(storage)]
     #[derive(Default)]
     pub struct DocumentManagement {
-        // Mapping documentId to owner accountId
-        document_owner: Mapping<DocumentId, AccountId, ManualKey<OWNER_KEY>>,
-        document_content: Mapping<DocumentId, Hash, ManualKey<DOCUMENT_COUNTER_KEY>>,
-        document_metadata: Mapping<DocumentId, Hash, ManualKey<METADATA_KEY>>,
-        // store the file on IPFS, map the document id to the ipfs addr
-        document_location: Mapping<DocumentId, IPFSaddr, ManualKey<LOCATION_KEY>>,
+        // owner, content, metadata, location, approval and deposit of a document packed
+        // together, read and written with a single storage access
+        documents: Mapping<DocumentId, DocumentRecord, ManualKey<DOCUMENT_RECORD_KEY>>,
+        // layout version 2 and older kept every field of a document in its own mapping,
+        // only read by the migration to version 3
+        legacy_owner: Mapping<DocumentId, AccountId, ManualKey<OWNER_KEY>>,
+        legacy_content: Mapping<DocumentId, Hash, ManualKey<CONTENT_KEY>>,
+        legacy_metadata: Mapping<DocumentId, Hash, ManualKey<METADATA_KEY>>,
+        legacy_location: Mapping<DocumentId, IPFSaddr, ManualKey<LOCATION_KEY>>,
+        legacy_approvals: Mapping<DocumentId, AccountId, ManualKey<DOCUMENT_APPROVAL_KEY>>,
+        legacy_deposits: Mapping<DocumentId, StorageDeposit, ManualKey<DOCUMENT_DEPOSIT_KEY>>,
         // store total document owned by this accountId
         owned_document_counter: Mapping<AccountId, u32, ManualKey<DOCUMENT_COUNTER_KEY>>,
         // store the operator accounts that can manage the documents on the owner
         operator_approvals: Mapping<(AccountId, AccountId), bool, ManualKey<OPRATOR_APPROVAL_KEY>>,
-        // store an approved account that can only interact with that particuler documentId
-        document_approvals: Mapping<DocumentId, AccountId, ManualKey<DOCUMENT_APPROVAL_KEY>>,
+        // off-chain signatures verified against the content hash, indexed per document
+        document_attestations: Mapping<(DocumentId, u32), Attestation, ManualKey<ATTESTATION_KEY>>,
+        attestation_counter: Mapping<DocumentId, u32, ManualKey<ATTESTATION_COUNTER_KEY>>,
+        // the content hash each signer last attested, prevents duplicated attestations
+        attested_signers: Mapping<(DocumentId, SignerKey), Hash, ManualKey<ATTESTED_SIGNER_KEY>>,
+        // next permit nonce of each owner, replay protection for signed approvals
+        permit_nonces: Mapping<AccountId, u64, ManualKey<PERMIT_NONCE_KEY>>,
+        // price and terms the owner sells access licenses of a document for
+        license_terms: Mapping<DocumentId, LicenseTerms, ManualKey<LICENSE_TERMS_KEY>>,
+        // licenses bought by an account for a document
+        licenses: Mapping<(AccountId, DocumentId), License, ManualKey<LICENSE_KEY>>,
+        // accounts that bought a license of a document, their licenses go with the document
+        licensees: Mapping<(DocumentId, u32), AccountId, ManualKey<LICENSEE_KEY>>,
+        licensee_counter: Mapping<DocumentId, u32, ManualKey<LICENSEE_COUNTER_KEY>>,
+        // documents the owner put up for sale
+        listings: Mapping<DocumentId, Listing, ManualKey<LISTING_KEY>>,
+        // value escrowed by a buyer as an offer for a document
+        offers: Mapping<(DocumentId, AccountId), Balance, ManualKey<OFFER_KEY>>,
+        // buyers with an offer on a document, refunded when the document is destroyed
+        offer_buyers: Mapping<(DocumentId, u32), AccountId, ManualKey<OFFER_BUYER_KEY>>,
+        offer_counter: Mapping<DocumentId, u32, ManualKey<OFFER_COUNTER_KEY>>,
+        offer_position: Mapping<(DocumentId, AccountId), u32, ManualKey<OFFER_POSITION_KEY>>,
+        // royalty of the original author, recorded when the document is minted
+        royalties: Mapping<DocumentId, Royalty, ManualKey<ROYALTY_KEY>>,
+        // account renting the usage rights of a document until the expiry block
+        document_users: Mapping<DocumentId, DocumentUser, ManualKey<DOCUMENT_USER_KEY>>,
+        // document key encrypted to the public key of each recipient
+        key_envelopes: Mapping<(DocumentId, AccountId), KeyEnvelope, ManualKey<KEY_ENVELOPE_KEY>>,
+        envelope_recipients:
+            Mapping<DocumentId, Vec<AccountId>, ManualKey<ENVELOPE_RECIPIENTS_KEY>>,
+        // bumped every time the owner rotate the document key
+        document_key_version: Mapping<DocumentId, u32, ManualKey<KEY_VERSION_KEY>>,
+        // typed links between documents, indexed from both ends for paged queries
+        outgoing_links: Mapping<(DocumentId, u32), DocumentLink, ManualKey<OUTGOING_LINK_KEY>>,
+        outgoing_link_counter: Mapping<DocumentId, u32, ManualKey<OUTGOING_LINK_COUNTER_KEY>>,
+        incoming_links: Mapping<(DocumentId, u32), DocumentLink, ManualKey<INCOMING_LINK_KEY>>,
+        incoming_link_counter: Mapping<DocumentId, u32, ManualKey<INCOMING_LINK_COUNTER_KEY>>,
+        linked: Mapping<LinkRef, LinkPosition, ManualKey<LINKED_KEY>>,
+        // the newer document that superseded a document
+        superseded_by: Mapping<DocumentId, DocumentId, ManualKey<SUPERSEDED_BY_KEY>>,
+        // the document a source asked to supersede, waiting for the consent of its owner
+        supersede_requests: Mapping<DocumentId, DocumentId, ManualKey<SUPERSEDE_REQUEST_KEY>>,
+        // account administrating the contract wide settings, set by the constructor
+        admin: Lazy<AccountId, ManualKey<ADMIN_KEY>>,
+        // named retention policies defined by the admin
+        retention_policies: Mapping<Hash, RetentionPolicy, ManualKey<RETENTION_POLICY_KEY>>,
+        document_retention:
+            Mapping<DocumentId, DocumentRetention, ManualKey<DOCUMENT_RETENTION_KEY>>,
+        // deposit charged for every owner, content, metadata, location and approval entry
+        deposit_per_entry: Lazy<Balance, ManualKey<DEPOSIT_PER_ENTRY_KEY>>,
+        // number of documents an account may hold, `None` for no limit
+        default_quota: Lazy<Option<u32>, ManualKey<DEFAULT_QUOTA_KEY>>,
+        // per account limits overriding the default quota
+        account_quotas: Mapping<AccountId, u32, ManualKey<ACCOUNT_QUOTA_KEY>>,
+        // circuit breakers switched by the admin
+        paused: Mapping<PauseScope, bool, ManualKey<PAUSED_KEY>>,
+        // account allowed to propose and apply new code, set by the constructor
+        upgrade_authority: Lazy<AccountId, ManualKey<UPGRADE_AUTHORITY_KEY>>,
+        upgrade_delay: Lazy<BlockNumber, ManualKey<UPGRADE_DELAY_KEY>>,
+        pending_upgrade: Lazy<Option<PendingUpgrade>, ManualKey<PENDING_UPGRADE_KEY>>,
+        // a shorter delay waiting for the current delay to pass
+        pending_upgrade_delay: Lazy<Option<PendingDelay>, ManualKey<PENDING_UPGRADE_DELAY_KEY>>,
+        // layout version of the stored data, see `CURRENT_LAYOUT_VERSION`
+        layout_version: Lazy<u32, ManualKey<LAYOUT_VERSION_KEY>>,
+        // progress of the running migration, `None` when no migration is running
+        migration: Lazy<Option<MigrationState>, ManualKey<MIGRATION_KEY>>,
+        // every existing document id, walked in chunks by the migrations
+        document_index: Mapping<u32, DocumentId, ManualKey<DOCUMENT_INDEX_KEY>>,
+        document_index_position: Mapping<DocumentId, u32, ManualKey<DOCUMENT_INDEX_POSITION_KEY>>,
+        document_index_len: Lazy<u32, ManualKey<DOCUMENT_INDEX_LEN_KEY>>,
+        // `DocumentAcounts` instance minting and content writes are checked against
+        access_requirement: Lazy<Option<AccessRequirement>, ManualKey<ACCESS_REQUIREMENT_KEY>>,
+        // authors who accepted to be named on the document, kept across ownership transfers
+        document_authors: Mapping<DocumentId, Vec<AccountId>, ManualKey<DOCUMENT_AUTHORS_KEY>>,
+        // accounts the owner named that did not accept yet
+        pending_authors: Mapping<DocumentId, Vec<AccountId>, ManualKey<PENDING_AUTHORS_KEY>>,
+        // documents each account is a confirmed author of, for paged queries
+        authored_documents: Mapping<(AccountId, u32), DocumentId, ManualKey<AUTHORED_DOCUMENT_KEY>>,
+        authored_counter: Mapping<AccountId, u32, ManualKey<AUTHORED_COUNTER_KEY>>,
+        authored_position: Mapping<(AccountId, DocumentId), u32, ManualKey<AUTHORED_POSITION_KEY>>,
+        // co-owners of the documents held in custody by the contract account
+        co_ownerships: Mapping<DocumentId, CoOwnership, ManualKey<CO_OWNERSHIP_KEY>>,
+        // documents each account co-owns, walked by the recovery of a lost account
+        co_owned_documents: Mapping<(AccountId, u32), DocumentId, ManualKey<CO_OWNED_DOCUMENT_KEY>>,
+        co_owned_position: Mapping<(AccountId, DocumentId), u32, ManualKey<CO_OWNED_POSITION_KEY>>,
+        co_owned_index_len: Mapping<AccountId, u32, ManualKey<CO_OWNED_INDEX_LEN_KEY>>,
+        // actions on a co-owned document waiting for the confirmations of the co-owners
+        proposals: Mapping<(DocumentId, u32), Proposal, ManualKey<PROPOSAL_KEY>>,
+        proposal_counter: Mapping<DocumentId, u32, ManualKey<PROPOSAL_COUNTER_KEY>>,
+        // documents of every owner, filled for existing documents by `migrate(3, 4)`
+        owned_documents: Mapping<(AccountId, u32), DocumentId, ManualKey<OWNED_DOCUMENT_KEY>>,
+        owned_position: Mapping<DocumentId, u32, ManualKey<OWNED_POSITION_KEY>>,
+        owned_index_len: Mapping<AccountId, u32, ManualKey<OWNED_INDEX_LEN_KEY>>,
+        // guardians able to move the documents of an account that lost its key
+        recovery_configs: Mapping<AccountId, RecoveryConfig, ManualKey<RECOVERY_CONFIG_KEY>>,
+        // open recovery of the lost account
+        recoveries: Mapping<AccountId, Recovery, ManualKey<RECOVERY_KEY>>,
+        // documents whose location and key envelopes are only handed out by `document_access`
+        audited_access: Mapping<DocumentId, bool, ManualKey<AUDITED_ACCESS_KEY>>,
+        // every `document_access` call of a document
+        access_log: Mapping<(DocumentId, u32), AccessRecord, ManualKey<ACCESS_LOG_KEY>>,
+        access_log_len: Mapping<DocumentId, u32, ManualKey<ACCESS_LOG_LEN_KEY>>,
+        // deliveries of a document
+        deliveries: Mapping<(DocumentId, u32), Delivery, ManualKey<DELIVERY_KEY>>,
+        delivery_counter: Mapping<DocumentId, u32, ManualKey<DELIVERY_COUNTER_KEY>>,
+        // the delivery a recipient has yet to acknowledge
+        pending_deliveries: Mapping<(DocumentId, AccountId), u32, ManualKey<PENDING_DELIVERY_KEY>>,
+        received_deliveries:
+            Mapping<(AccountId, u32), DeliveryRef, ManualKey<RECEIVED_DELIVERY_KEY>>,
+        received_counter: Mapping<AccountId, u32, ManualKey<RECEIVED_COUNTER_KEY>>,
+        // where a delivery sits in the list of its recipient
+        received_position: Mapping<DeliveryRef, u32, ManualKey<RECEIVED_POSITION_KEY>>,
+        // templates backed by a document, owned by the owner of that document
+        templates: Mapping<u32, Template, ManualKey<TEMPLATE_KEY>>,
+        template_versions: Mapping<(u32, u32), TemplateVersion, ManualKey<TEMPLATE_VERSION_KEY>>,
+        template_counter: Lazy<u32, ManualKey<TEMPLATE_COUNTER_KEY>>,
+        // the template a document backs, a document backs a single template
+        backed_templates: Mapping<DocumentId, u32, ManualKey<BACKED_TEMPLATE_KEY>>,
+        // template version a document got instantiated from
+        document_templates: Mapping<DocumentId, TemplateUse, ManualKey<DOCUMENT_TEMPLATE_KEY>>,
+        // first candidate for the next id allocated by the contract
+        next_document_id: Lazy<DocumentId, ManualKey<NEXT_DOCUMENT_ID_KEY>>,
     }
 
     #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
@@ -62,46 +299,546 @@ pub mod document_management {
         NoDataFound,
         AccountNotFound,
         CannotFetchValue,
+        InvalidSignature,
+        PermitExpired,
+        InvalidNonce,
+        IncorrectPayment,
+        TransferFailed,
+        DocumentIsListed,
+        NotListed,
+        OfferNotFound,
+        InvalidRoyalty,
+        EnvelopeTooLarge,
+        TooManyRecipients,
+        EnvelopeMismatch,
+        LinkCycle,
+        LineageTooDeep,
+        NotAdmin,
+        InvalidRetention,
+        RetentionActive,
+        NotExpired,
+        QuotaExceeded,
+        OperationPaused,
+        NotUpgradeAuthority,
+        UpgradeNotReady,
+        UpgradeFailed,
+        MigrationInProgress,
+        WrongLayoutVersion,
+        IdentityNotRegistered,
+        MissingRole,
+        AccountsCallFailed,
+        TooManyAuthors,
+        NotCoOwner,
+        InvalidThreshold,
+        ProposalExpired,
+        NotGuardian,
+        RecoveryNotReady,
+        AuditedAccessRequired,
+        DeadlinePassed,
+        WrongRevision,
+        TemplateNotFound,
+        TemplateDeprecated,
+    }
+
+    // public key of an account that signed the document hash off-chain
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum SignerKey {
+        Ecdsa([u8; 33]),
+        Sr25519([u8; 32]),
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Attestation {
+        pub signer: SignerKey,
+        // the content hash that was signed
+        pub content_hash: Hash,
+        // account that submitted the signature, usually a relayer
+        pub submitter: AccountId,
+        pub block: BlockNumber,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct LicenseTerms {
+        pub price: Balance,
+        // hash of the license agreement the buyer accepts
+        pub terms_hash: Hash,
+        // number of blocks a license stays valid, `None` for a perpetual license
+        pub duration: Option<BlockNumber>,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct License {
+        pub terms_hash: Hash,
+        pub price: Balance,
+        pub purchased_at: BlockNumber,
+        pub expires_at: Option<BlockNumber>,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Listing {
+        pub seller: AccountId,
+        pub price: Balance,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Royalty {
+        pub receiver: AccountId,
+        pub basis_points: u16,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct DocumentUser {
+        pub user: AccountId,
+        // the block the usage rights lapse at
+        pub expires: BlockNumber,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct KeyEnvelope {
+        // version of the document key sealed in this envelope
+        pub key_version: u32,
+        pub data: Vec<u8>,
+    }
+
+    // relation of a source document to the target document of a link
+    #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum LinkKind {
+        Supersedes,
+        Amends,
+        DerivedFrom,
+        Attachment,
+        References,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct DocumentLink {
+        pub source: DocumentId,
+        pub target: DocumentId,
+        pub kind: LinkKind,
+    }
+
+    // where a link sits in the outgoing list of its source and the incoming list of its target
+    #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct LinkPosition {
+        pub outgoing: u32,
+        pub incoming: u32,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct RetentionPolicy {
+        // blocks after the assignment before the document may be burned
+        pub min_blocks: BlockNumber,
+        // blocks after the assignment the document may be purged by anyone
+        pub max_blocks: Option<BlockNumber>,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct DocumentRetention {
+        pub policy: Hash,
+        pub retain_until: BlockNumber,
+        pub expires_at: Option<BlockNumber>,
+    }
+
+    // storage deposit held by the contract for the entries of a document
+    #[derive(Encode, Decode, Debug, Default, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct StorageDeposit {
+        pub entries: u32,
+        pub amount: Balance,
+    }
+
+    // everything small stored per document, the bigger and optional data like attestations
+    // or key envelopes stay in their own mappings
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct DocumentRecord {
+        pub owner: AccountId,
+        pub content: Option<Hash>,
+        pub metadata: Option<Hash>,
+        // store the file on IPFS
+        pub location: Option<IPFSaddr>,
+        // an approved account that can only interact with this document
+        pub approved: Option<AccountId>,
+        pub deposit: StorageDeposit,
+    }
+
+    // category of operations the admin can pause, `All` pause every operation
+    #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum PauseScope {
+        All,
+        Minting,
+        Transfers,
+        ContentWrites,
+        Approvals,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct PendingUpgrade {
+        pub code_hash: Hash,
+        // first block `execute_upgrade` can apply the code hash at
+        pub executable_at: BlockNumber,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct PendingDelay {
+        pub delay: BlockNumber,
+        // first block the shorter delay applies at
+        pub effective_at: BlockNumber,
+    }
+
+    // the document is held by the contract account, `threshold` of `owners` have to
+    // confirm a proposal before it executes
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CoOwnership {
+        pub owners: Vec<AccountId>,
+        pub threshold: u32,
+        // the owner that put the document in custody, storage deposits are refunded to it
+        pub creator: AccountId,
+        // proposals made before the co-ownership got created are void
+        pub first_proposal: u32,
+    }
+
+    // what a co-owner can propose, the owner only messages of a single owned document
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum CoOwnerAction {
+        // move the document out of custody, ending the co-ownership
+        Transfer { to: AccountId },
+        Burn,
+        ReviseContent { content: Hash },
+        Approve { to: AccountId },
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Proposal {
+        pub action: CoOwnerAction,
+        pub proposer: AccountId,
+        pub confirmations: Vec<AccountId>,
+        pub expires_at: BlockNumber,
+        // paid by the proposer for the stored proposal, refunded once it is executed or
+        // cancelled
+        pub deposit: Balance,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct RecoveryConfig {
+        pub guardians: Vec<AccountId>,
+        pub threshold: u32,
+        // blocks the owner has to veto a recovery once enough guardians approved it
+        pub delay: BlockNumber,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Recovery {
+        pub new_account: AccountId,
+        pub approvals: Vec<AccountId>,
+        // set when the approvals reach the threshold
+        pub executable_at: Option<BlockNumber>,
+        // documents already moved, the recovery cannot be vetoed once it started
+        pub moved: u32,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AccessRecord {
+        pub accessor: AccountId,
+        // code of the reason given by the accessor, defined off-chain
+        pub purpose: u32,
+        pub block: BlockNumber,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum DeliveryStatus {
+        Pending,
+        Acknowledged,
+        // the deadline passed before the recipient acknowledged
+        Expired,
+        // withdrawn by the sender before the recipient acknowledged
+        Cancelled,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Delivery {
+        pub sender: AccountId,
+        pub recipient: AccountId,
+        // content hash of the document when it got delivered
+        pub revision: Hash,
+        pub delivered_at: BlockNumber,
+        // last block the recipient can acknowledge in
+        pub deadline: Option<BlockNumber>,
+        pub status: DeliveryStatus,
+        // block the delivery got acknowledged or expired in
+        pub settled_at: Option<BlockNumber>,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Template {
+        // documents instantiated from the template link to this one
+        pub document_id: DocumentId,
+        pub latest_version: u32,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct TemplateVersion {
+        pub content: Hash,
+        // hash of the schema describing the fillable fields
+        pub schema: Hash,
+        pub deprecated: bool,
+        pub registered_at: BlockNumber,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct TemplateUse {
+        pub template_id: u32,
+        pub version: u32,
+        pub field_values: Hash,
+    }
+
+    // accounts have to be registered in the `accounts` contract, and hold `role` if set
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AccessRequirement {
+        pub accounts: AccountId,
+        pub role: Option<Role>,
+    }
+
+    // migration from one layout version to the next, `cursor` is the next position of
+    // the document index to process
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct MigrationState {
+        pub from: u32,
+        pub to: u32,
+        pub cursor: u32,
+    }
+
+    #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct QuotaUsage {
+        pub used: u32,
+        pub limit: Option<u32>,
     }
 
+    // everything stored about a document, returned by `document_info`
     #[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-    pub enum UpdateMessage {
-        ContentUpdate,
-        OwnverUpdate,
-        DocumentDelete,
-        MetadataUpdate,
+    pub struct DocumentInfo {
+        pub owner: AccountId,
+        pub content: Option<Hash>,
+        pub metadata: Option<Hash>,
+        pub location: Option<IPFSaddr>,
+        pub superseded_by: Option<DocumentId>,
+        pub deposit: Balance,
     }
 
-    // Emit event when document get transfer
+    // The document events carry the previous and the new value so indexers don't have to
+    // query the state again. Their signature topics are pinned, see the README, and stay
+    // the same when the events get renamed or moved
+
+    //Emit event when the content hash of a document get set or removed (`content: None`)
     #[ink(event)]
-    pub struct Transfer {
+    #[ink(signature_topic = "29415a950d90e3fa12182156c5b9e429c889fe97db4b890b2323cd31153545d2")]
+    pub struct ContentAnchored {
         #[ink(topic)]
-        from: Option<AccountId>,
+        id: DocumentId,
         #[ink(topic)]
-        to: Option<AccountId>,
+        by: AccountId,
+        previous: Option<Hash>,
+        #[ink(topic)]
+        content: Option<Hash>,
+        block: BlockNumber,
+    }
+
+    //Emit event when the metadata hash of a document get set or removed (`metadata: None`)
+    #[ink(event)]
+    #[ink(signature_topic = "98c3dc999c4940ef6f3fa3a0ed7b95e8b6225bfb258bf0bfeb4ce23e6fb5047e")]
+    pub struct MetadataChanged {
         #[ink(topic)]
         id: DocumentId,
+        #[ink(topic)]
+        by: AccountId,
+        previous: Option<Hash>,
+        #[ink(topic)]
+        metadata: Option<Hash>,
+        block: BlockNumber,
     }
 
-    //Emit event when a proxy approve an document
     #[ink(event)]
-    pub struct ProxyUpdated {
+    #[ink(signature_topic = "c9781d9706af23d510c4d7caeb17c25599e968d31cf7c1be2b3e87c750ee6642")]
+    pub struct LocationAdded {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        by: AccountId,
+        previous: Option<IPFSaddr>,
+        #[ink(topic)]
+        location: IPFSaddr,
+        block: BlockNumber,
+    }
+
+    #[ink(event)]
+    #[ink(signature_topic = "7337e314b7253b69145ee1ed7f6bf9220727c271d620c88dffac8e5dea2ae09d")]
+    pub struct LocationRemoved {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        by: AccountId,
+        previous: IPFSaddr,
+        block: BlockNumber,
+    }
+
+    //Emit event when the owner of a document change, `from` is `None` on mint
+    #[ink(event)]
+    #[ink(signature_topic = "cfb46d41ad1c2ab8e8805aee9fb7214c2414600000d5305f116114bff3c5b506")]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        id: DocumentId,
         #[ink(topic)]
         from: Option<AccountId>,
         #[ink(topic)]
         to: Option<AccountId>,
+        block: BlockNumber,
+    }
+
+    //Emit event when a document get removed, by its owner or by a purge
+    #[ink(event)]
+    #[ink(signature_topic = "b0af01f453cef71712d0f60d8f00f24fe7e588728c1874511744b9a9bebf4aa0")]
+    pub struct Burned {
         #[ink(topic)]
         id: DocumentId,
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+        content: Option<Hash>,
+        block: BlockNumber,
     }
 
-    //Emit event when Document get updated
+    //Emit event when a proxy approve an document
     #[ink(event)]
-    pub struct DocumentUpdated {
+    pub struct ProxyUpdated {
         #[ink(topic)]
-        action: UpdateMessage,
+        from: Option<AccountId>,
         #[ink(topic)]
-        from: AccountId,
+        to: Option<AccountId>,
         #[ink(topic)]
         id: DocumentId,
     }
@@ -134,333 +871,3899 @@ pub mod document_management {
         id: DocumentId,
     }
 
-    impl DocumentManagement {
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            Self::default()
-        }
-        //Create a new document
-        #[ink(message)]
-        pub fn document_new(&mut self, document_id: DocumentId) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            self.add_document_to(&caller, document_id)?;
-            self.increase_documents_count(&caller);
-            self.env().emit_event(Transfer {
-                // accountid like this indicate a null placeholder, don't want to known
-                // who create the NFT.
-                from: Some(AccountId::from([0x0; 32])),
-                to: Some(caller),
-                id: document_id,
-            });
-            Ok(())
-        }
-        // check if document exist with an owner
-        #[ink(message)]
-        pub fn verify_document_owner(&self, document_id: DocumentId) -> bool {
-            self.document_owner.contains(document_id)
-        }
-        // burn the document, only owner can do this
-        #[ink(message)]
-        pub fn burn_document(&mut self, document_id: DocumentId) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            if self.verify_document_owner(document_id) == false {
-                return Err(DocumentError::DocumentNotFound);
-            }
-            if self.check_owner_owned_document(&caller, &document_id) == false {
-                return Err(DocumentError::NotOwner);
-            }
-            match self.document_owner.get(document_id) {
-                Some(_) => {
-                    self.decrease_documents_count(&caller);
-                    self.document_owner.remove(document_id);
-                    self.env().emit_event(DocumentUpdated {
-                        action: UpdateMessage::DocumentDelete,
-                        from: caller,
+    //Emit event when an off-chain signature over the document get verified
+    #[ink(event)]
+    pub struct DocumentAttested {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        submitter: AccountId,
+        signer: SignerKey,
+        content_hash: Hash,
+    }
+
+    //Emit event when the owner change the license terms of a document
+    #[ink(event)]
+    pub struct LicenseTermsUpdated {
+        #[ink(topic)]
+        id: DocumentId,
+        terms: Option<LicenseTerms>,
+    }
+
+    //Emit event when an account buy a license for a document
+    #[ink(event)]
+    pub struct LicensePurchased {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        buyer: AccountId,
+        price: Balance,
+        expires_at: Option<BlockNumber>,
+    }
+
+    //Emit event when the owner put a document up for sale
+    #[ink(event)]
+    pub struct DocumentListed {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        seller: AccountId,
+        price: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ListingCancelled {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        seller: AccountId,
+    }
+
+    //Emit event when a buyer escrow an offer for a document
+    #[ink(event)]
+    pub struct OfferMade {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        buyer: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct OfferCancelled {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        buyer: AccountId,
+        amount: Balance,
+    }
+
+    //Emit event when the ownership of a document get sold
+    #[ink(event)]
+    pub struct DocumentSold {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        seller: AccountId,
+        #[ink(topic)]
+        buyer: AccountId,
+        price: Balance,
+    }
+
+    //Emit event when a sale pay the royalty of a document
+    #[ink(event)]
+    pub struct RoyaltyPaid {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        receiver: AccountId,
+        amount: Balance,
+    }
+
+    //Emit event when the user of a document get assigned or cleared
+    #[ink(event)]
+    pub struct UpdateUser {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        user: Option<AccountId>,
+        expires: BlockNumber,
+    }
+
+    //Emit event when the owner share the document key with a recipient
+    #[ink(event)]
+    pub struct KeyEnvelopeAdded {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        recipient: AccountId,
+        key_version: u32,
+    }
+
+    #[ink(event)]
+    pub struct KeyEnvelopeRevoked {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        recipient: AccountId,
+    }
+
+    //Emit event when the owner re-key all recipients of a document
+    #[ink(event)]
+    pub struct DocumentKeyRotated {
+        #[ink(topic)]
+        id: DocumentId,
+        key_version: u32,
+        recipients: u32,
+    }
+
+    //Emit event when the owner link a document to another one
+    #[ink(event)]
+    pub struct DocumentLinked {
+        #[ink(topic)]
+        source: DocumentId,
+        #[ink(topic)]
+        target: DocumentId,
+        kind: LinkKind,
+    }
+
+    //Emit event when a document ask to supersede a document of another owner
+    #[ink(event)]
+    pub struct SupersedeRequested {
+        #[ink(topic)]
+        source: DocumentId,
+        #[ink(topic)]
+        target: DocumentId,
+    }
+
+    //Emit event when the owner of the target refuse to be superseded
+    #[ink(event)]
+    pub struct SupersedeRejected {
+        #[ink(topic)]
+        source: DocumentId,
+        #[ink(topic)]
+        target: DocumentId,
+    }
+
+    #[ink(event)]
+    pub struct RetentionAssigned {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        policy: Hash,
+        retain_until: BlockNumber,
+        expires_at: Option<BlockNumber>,
+    }
+
+    //Emit event when an expired document get purged
+    #[ink(event)]
+    pub struct DocumentPurged {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        purged_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        scope: PauseScope,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        scope: PauseScope,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    //Emit event when a new code hash get proposed, users can react until `executable_at`
+    #[ink(event)]
+    pub struct UpgradeProposed {
+        #[ink(topic)]
+        code_hash: Hash,
+        #[ink(topic)]
+        by: AccountId,
+        executable_at: BlockNumber,
+    }
+
+    #[ink(event)]
+    pub struct UpgradeCancelled {
+        #[ink(topic)]
+        code_hash: Hash,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct UpgradeExecuted {
+        #[ink(topic)]
+        code_hash: Hash,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct UpgradeDelayChanged {
+        old_delay: BlockNumber,
+        new_delay: BlockNumber,
+        effective_at: BlockNumber,
+    }
+
+    //Emit event when the stored data reached a new layout version
+    #[ink(event)]
+    pub struct Migrated {
+        #[ink(topic)]
+        from: u32,
+        #[ink(topic)]
+        to: u32,
+    }
+
+    //Emit event when the owner name an account as author, pending its consent
+    #[ink(event)]
+    pub struct AuthorNominated {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        author: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AuthorConfirmed {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        author: AccountId,
+    }
+
+    //Emit event when a nomination is declined or withdrawn, or an author resign
+    #[ink(event)]
+    pub struct AuthorRemoved {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        author: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    //Emit event when the owner share the document with co-owners
+    #[ink(event)]
+    pub struct CoOwnershipCreated {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        creator: AccountId,
+        owners: Vec<AccountId>,
+        threshold: u32,
+    }
+
+    //Emit event when a co-owner propose an action on the document
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        action: CoOwnerAction,
+        expires_at: BlockNumber,
+    }
+
+    //Emit event when a co-owner confirm a proposal
+    #[ink(event)]
+    pub struct ProposalConfirmed {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        by: AccountId,
+        confirmations: u32,
+    }
+
+    //Emit event when a co-owner withdraw its confirmation
+    #[ink(event)]
+    pub struct ProposalRevoked {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    //Emit event when the proposer drop its proposal
+    #[ink(event)]
+    pub struct ProposalCancelled {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        proposal_id: u32,
+    }
+
+    //Emit event when a proposal reach the threshold and get executed
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    //Emit event when an account set or remove its recovery guardians
+    #[ink(event)]
+    pub struct RecoveryConfigured {
+        #[ink(topic)]
+        account: AccountId,
+        config: Option<RecoveryConfig>,
+    }
+
+    //Emit event when a guardian start the recovery of a lost account
+    #[ink(event)]
+    pub struct RecoveryInitiated {
+        #[ink(topic)]
+        lost: AccountId,
+        #[ink(topic)]
+        new_account: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    //Emit event when a guardian approve a recovery
+    #[ink(event)]
+    pub struct RecoveryApproved {
+        #[ink(topic)]
+        lost: AccountId,
+        #[ink(topic)]
+        by: AccountId,
+        approvals: u32,
+        executable_at: Option<BlockNumber>,
+    }
+
+    //Emit event when the owner veto the recovery of its account
+    #[ink(event)]
+    pub struct RecoveryVetoed {
+        #[ink(topic)]
+        lost: AccountId,
+    }
+
+    //Emit event when every document of the lost account got moved
+    #[ink(event)]
+    pub struct RecoveryFinalized {
+        #[ink(topic)]
+        lost: AccountId,
+        #[ink(topic)]
+        new_account: AccountId,
+        moved: u32,
+    }
+
+    //Emit event when the owner turn the audited access mode of a document on or off
+    #[ink(event)]
+    pub struct AuditedAccessChanged {
+        #[ink(topic)]
+        id: DocumentId,
+        enabled: bool,
+    }
+
+    //Emit event when an account record its access to an audited document
+    #[ink(event)]
+    pub struct DocumentAccessed {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        accessor: AccountId,
+        purpose: u32,
+        block: BlockNumber,
+    }
+
+    //Emit event when the owner deliver the document to a recipient
+    #[ink(event)]
+    pub struct DocumentDelivered {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        sender: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        delivery_id: u32,
+        revision: Hash,
+        deadline: Option<BlockNumber>,
+    }
+
+    //Emit event when the recipient acknowledge the delivered revision
+    #[ink(event)]
+    pub struct DeliveryAcknowledged {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        recipient: AccountId,
+        delivery_id: u32,
+        revision: Hash,
+        block: BlockNumber,
+    }
+
+    //Emit event when a delivery got expired after its deadline
+    #[ink(event)]
+    pub struct DeliveryExpired {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        recipient: AccountId,
+        delivery_id: u32,
+        block: BlockNumber,
+    }
+
+    //Emit event when the sender withdraw a pending delivery
+    #[ink(event)]
+    pub struct DeliveryCancelled {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        recipient: AccountId,
+        delivery_id: u32,
+        block: BlockNumber,
+    }
+
+    //Emit event when an owner turn its document into a template
+    #[ink(event)]
+    pub struct TemplateRegistered {
+        #[ink(topic)]
+        template_id: u32,
+        #[ink(topic)]
+        document_id: DocumentId,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct TemplateVersionAdded {
+        #[ink(topic)]
+        template_id: u32,
+        version: u32,
+        content: Hash,
+        schema: Hash,
+    }
+
+    #[ink(event)]
+    pub struct TemplateVersionDeprecated {
+        #[ink(topic)]
+        template_id: u32,
+        version: u32,
+    }
+
+    //Emit event when a document get minted from a template
+    #[ink(event)]
+    pub struct DocumentFromTemplate {
+        #[ink(topic)]
+        id: DocumentId,
+        #[ink(topic)]
+        template_id: u32,
+        version: u32,
+        field_values: Hash,
+    }
+
+    impl DocumentManagement {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            instance.admin.set(&Self::env().caller());
+            instance.upgrade_authority.set(&Self::env().caller());
+            instance.upgrade_delay.set(&DEFAULT_UPGRADE_DELAY);
+            instance.layout_version.set(&CURRENT_LAYOUT_VERSION);
+            instance
+        }
+        //Create a new document
+        #[ink(message, payable)]
+        pub fn document_new(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            self.mint_document(&caller, document_id)
+        }
+        //Create a new document that pay a royalty to `receiver` on every sale
+        #[ink(message, payable)]
+        pub fn document_new_with_royalty(
+            &mut self,
+            document_id: DocumentId,
+            receiver: AccountId,
+            basis_points: u16,
+        ) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            if basis_points > ROYALTY_DENOMINATOR {
+                return Err(DocumentError::InvalidRoyalty);
+            }
+            self.mint_document(&caller, document_id)?;
+            self.royalties.insert(
+                document_id,
+                &Royalty {
+                    receiver,
+                    basis_points,
+                },
+            );
+            Ok(())
+        }
+        //Create a new document naming its authors, each of them has to accept the nomination.
+        //The caller is named right away when in the list
+        #[ink(message, payable)]
+        pub fn document_new_with_authors(
+            &mut self,
+            document_id: DocumentId,
+            authors: Vec<AccountId>,
+        ) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            self.mint_document(&caller, document_id)?;
+            for author in authors {
+                self.nominate_author(&caller, document_id, author)?;
+            }
+            Ok(())
+        }
+        // check if document exist with an owner
+        #[ink(message)]
+        pub fn verify_document_owner(&self, document_id: DocumentId) -> bool {
+            self.documents.contains(document_id)
+        }
+        // burn the document, only owner can do this
+        #[ink(message)]
+        pub fn burn_document(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::Transfers)?;
+            let caller = self.env().caller();
+            let record = self.owned_record(&caller, document_id)?;
+            self.ensure_burnable(document_id)?;
+            self.clear_document(document_id, record)
+        }
+        // get everything stored about the document in one call
+        #[ink(message)]
+        pub fn document_info(&self, document_id: DocumentId) -> DocumentResult<DocumentInfo> {
+            let record = self.record(document_id)?;
+            Ok(DocumentInfo {
+                owner: record.owner,
+                content: record.content,
+                metadata: record.metadata,
+                location: record.location,
+                superseded_by: self.superseded_by.get(document_id),
+                deposit: record.deposit.amount,
+            })
+        }
+        //get the document owner
+        #[ink(message)]
+        pub fn document_owner_get(&self, document_id: DocumentId) -> DocumentResult<AccountId> {
+            self.record(document_id).map(|record| record.owner)
+        }
+        // check the owner of the document, used by other contracts like the dns. A co-owned
+        // document is owned by the contract account, its co-owners act through proposals
+        #[ink(message)]
+        pub fn document_owned_by(&self, owner: AccountId, document_id: DocumentId) -> bool {
+            self.documents.get(document_id).map(|record| record.owner) == Some(owner)
+        }
+        // check if the account is one of the co-owners of the document
+        #[ink(message)]
+        pub fn is_co_owner(&self, account: AccountId, document_id: DocumentId) -> bool {
+            self.co_ownerships
+                .get(document_id)
+                .is_some_and(|co_ownership| co_ownership.owners.contains(&account))
+        }
+        //create a new content for the document
+        #[ink(message, payable)]
+        pub fn document_content_new(
+            &mut self,
+            document_id: DocumentId,
+            cont: Hash,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_registered_writer(&caller)?;
+            let mut record = self.record(document_id)?;
+            match record.content {
+                Some(_) => Err(DocumentError::DocumentIdAlreadyExists),
+                None => {
+                    self.charge_deposit(&mut record)?;
+                    record.content = Some(cont);
+                    self.documents.insert(document_id, &record);
+                    self.env().emit_event(ContentAnchored {
+                        id: document_id,
+                        by: caller,
+                        previous: None,
+                        content: Some(cont),
+                        block: self.env().block_number(),
+                    });
+                    Ok(())
+                }
+            }
+        }
+        //get document content
+        #[ink(message)]
+        pub fn document_content_get(&self, document_id: DocumentId) -> Option<Hash> {
+            self.documents
+                .get(document_id)
+                .and_then(|record| record.content)
+        }
+        //check if document content exist
+        #[ink(message)]
+        pub fn verify_document_content(&self, document_id: DocumentId) -> bool {
+            self.document_content_get(document_id).is_some()
+        }
+        #[ink(message)]
+        pub fn remove_document_content(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_registered_writer(&caller)?;
+            let mut record = self.owned_record(&caller, document_id)?;
+            self.ensure_not_retained(document_id)?;
+            if let Some(previous) = record.content.take() {
+                self.refund_deposit_entry(&mut record, &caller)?;
+                self.documents.insert(document_id, &record);
+                self.env().emit_event(ContentAnchored {
+                    id: document_id,
+                    by: caller,
+                    previous: Some(previous),
+                    content: None,
+                    block: self.env().block_number(),
+                });
+            }
+            Ok(())
+        }
+        // add a new IPFS addr to the corresponding document
+        #[ink(message, payable)]
+        pub fn document_addr_new(
+            &mut self,
+            document_id: DocumentId,
+            ipfs_addr: IPFSaddr,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_registered_writer(&caller)?;
+            let mut record = self.owned_record(&caller, document_id)?;
+            // the location of an audited document only travels in the sealed envelopes
+            self.ensure_not_audited(document_id)?;
+            match record.location {
+                Some(_) => Err(DocumentError::DocumentIdAlreadyExists),
+                None => {
+                    self.charge_deposit(&mut record)?;
+                    record.location = Some(ipfs_addr);
+                    self.documents.insert(document_id, &record);
+                    self.env().emit_event(LocationAdded {
+                        id: document_id,
+                        by: caller,
+                        previous: None,
+                        location: ipfs_addr,
+                        block: self.env().block_number(),
+                    });
+                    Ok(())
+                }
+            }
+        }
+        // get the ipfs addr of the document
+        #[ink(message)]
+        pub fn document_location_get(&self, document_id: DocumentId) -> DocumentResult<IPFSaddr> {
+            match self
+                .documents
+                .get(document_id)
+                .and_then(|record| record.location)
+            {
+                Some(ipfs_addr) => Ok(ipfs_addr),
+                // an audited document never store its location
+                None => {
+                    self.ensure_not_audited(document_id)?;
+                    Err(DocumentError::DocumentNotFound)
+                }
+            }
+        }
+        // verify the IPFS is stored to the corresponded document id
+        #[ink(message)]
+        pub fn verify_document_location(&self, document_id: DocumentId) -> DocumentResult<bool> {
+            match self
+                .documents
+                .get(document_id)
+                .and_then(|record| record.location)
+            {
+                Some(_) => Ok(true),
+                None => Err(DocumentError::DocumentNotFound),
+            }
+        }
+        //remove the ipfs location of the corresponding document
+        #[ink(message)]
+        pub fn document_location_delete(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_registered_writer(&caller)?;
+            let mut record = self.owned_record(&caller, document_id)?;
+            self.ensure_not_retained(document_id)?;
+            match record.location.take() {
+                Some(previous) => {
+                    self.refund_deposit_entry(&mut record, &caller)?;
+                    self.documents.insert(document_id, &record);
+                    self.env().emit_event(LocationRemoved {
+                        id: document_id,
+                        by: caller,
+                        previous,
+                        block: self.env().block_number(),
+                    });
+                    Ok(())
+                }
+                None => Err(DocumentError::NoDataFound),
+            }
+        }
+        //create a new document metadata
+        #[ink(message, payable)]
+        pub fn document_metadata_new(
+            &mut self,
+            document_id: DocumentId,
+            metadata_hash: Hash,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_registered_writer(&caller)?;
+            let mut record = self.owned_record(&caller, document_id)?;
+            match record.metadata {
+                Some(_) => Err(DocumentError::DocumentIdAlreadyExists),
+                None => {
+                    self.charge_deposit(&mut record)?;
+                    record.metadata = Some(metadata_hash);
+                    self.documents.insert(document_id, &record);
+                    self.env().emit_event(MetadataChanged {
+                        id: document_id,
+                        by: caller,
+                        previous: None,
+                        metadata: Some(metadata_hash),
+                        block: self.env().block_number(),
+                    });
+                    Ok(())
+                }
+            }
+        }
+        //get document metadata
+        #[ink(message)]
+        pub fn document_metadata_get(&self, document_id: DocumentId) -> DocumentResult<Hash> {
+            match self
+                .documents
+                .get(document_id)
+                .and_then(|record| record.metadata)
+            {
+                Some(meta_hash) => Ok(meta_hash),
+                None => Err(DocumentError::DocumentNotFound),
+            }
+        }
+        //verify if the metadata stored is for the document
+        #[ink(message)]
+        pub fn verify_document_metadata(&self, document_id: DocumentId) -> DocumentResult<bool> {
+            match self
+                .documents
+                .get(document_id)
+                .and_then(|record| record.metadata)
+            {
+                Some(_) => Ok(true),
+                None => Err(DocumentError::DocumentNotFound),
+            }
+        }
+        //delete the document metadata from the storage
+        #[ink(message)]
+        pub fn document_metadata_delete(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_registered_writer(&caller)?;
+            let mut record = self.owned_record(&caller, document_id)?;
+            self.ensure_not_retained(document_id)?;
+            match record.metadata.take() {
+                Some(previous) => {
+                    self.refund_deposit_entry(&mut record, &caller)?;
+                    self.documents.insert(document_id, &record);
+                    self.env().emit_event(MetadataChanged {
+                        id: document_id,
+                        by: caller,
+                        previous: Some(previous),
+                        metadata: None,
+                        block: self.env().block_number(),
+                    });
+                    Ok(())
+                }
+                None => Err(DocumentError::NoDataFound),
+            }
+        }
+        //get the approved accountID for this documentID
+        #[ink(message)]
+        pub fn document_get_approve_acc(
+            &self,
+            document_id: DocumentId,
+        ) -> DocumentResult<AccountId> {
+            match self
+                .documents
+                .get(document_id)
+                .and_then(|record| record.approved)
+            {
+                Some(aproved_acc) => Ok(aproved_acc),
+                None => Err(DocumentError::AccountNotFound),
+            }
+        }
+        //get number of owned documents of an accountId
+        #[ink(message)]
+        pub fn numof_owned_documents(&self, owner: AccountId) -> u32 {
+            self.owned_document_counter.get(owner).unwrap_or(0)
+        }
+        //transfer document to another account, only owner or approved account can do it
+        #[ink(message)]
+        pub fn tranfer_to(&mut self, to: AccountId, document_id: DocumentId) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            self.transfer_document_from(&caller, &to, document_id)?;
+            Ok(())
+        }
+        // transfer approved for owned toke
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            document_id: DocumentId,
+        ) -> DocumentResult<()> {
+            self.transfer_document_from(&from, &to, document_id)?;
+            Ok(())
+        }
+        // approves the account to transfer the specific document on behalf of the caller
+        #[ink(message, payable)]
+        pub fn approve(&mut self, to: AccountId, document_id: DocumentId) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            self.approve_for(&caller, &to, document_id)?;
+            Ok(())
+        }
+        // approve or disapprove the operator fro all documentId of the caller
+        #[ink(message)]
+        pub fn set_approval_for_all(
+            &mut self,
+            to: AccountId,
+            approved: bool,
+        ) -> DocumentResult<()> {
+            self.approve_for_all(to, approved)?;
+            Ok(())
+        }
+        // get the approved accountId for this documentID
+        #[ink(message)]
+        pub fn get_approved_account(&self, document_id: DocumentId) -> DocumentResult<AccountId> {
+            match self
+                .documents
+                .get(document_id)
+                .and_then(|record| record.approved)
+            {
+                Some(acc) => Ok(acc),
+                None => Err(DocumentError::NoDataFound),
+            }
+        }
+        // return to see if the operator is approve by the ownver
+        #[ink(message)]
+        pub fn is_approve_for_all(
+            &self,
+            owner: AccountId,
+            operator: AccountId,
+        ) -> DocumentResult<bool> {
+            Ok(self.approved_for_all(&owner, &operator))
+        }
+        // verify an ECDSA signature over the document content hash and store it as attestation
+        #[ink(message)]
+        pub fn attest_ecdsa(
+            &mut self,
+            document_id: DocumentId,
+            signature: [u8; 65],
+            signer: [u8; 33],
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let content_hash = self.attested_content(document_id)?;
+            let message: [u8; 32] = content_hash.into();
+            let recovered = self
+                .env()
+                .ecdsa_recover(&signature, &message)
+                .map_err(|_| DocumentError::InvalidSignature)?;
+            if recovered != signer {
+                return Err(DocumentError::InvalidSignature);
+            }
+            self.record_attestation(document_id, SignerKey::Ecdsa(signer), content_hash)
+        }
+        // verify an sr25519 signature over the document content hash and store it as attestation
+        #[ink(message)]
+        pub fn attest_sr25519(
+            &mut self,
+            document_id: DocumentId,
+            signature: [u8; 64],
+            signer: [u8; 32],
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let content_hash = self.attested_content(document_id)?;
+            self.env()
+                .sr25519_verify(&signature, content_hash.as_ref(), &signer)
+                .map_err(|_| DocumentError::InvalidSignature)?;
+            self.record_attestation(document_id, SignerKey::Sr25519(signer), content_hash)
+        }
+        // approve `spender` for the document with a payload signed off-chain by the owner,
+        // so a relayer can submit the approval for the owner
+        #[ink(message, payable)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            document_id: DocumentId,
+            deadline: BlockNumber,
+            nonce: u64,
+            signature: [u8; 64],
+        ) -> DocumentResult<()> {
+            if self.env().block_number() > deadline {
+                return Err(DocumentError::PermitExpired);
+            }
+            if nonce != self.permit_nonce(owner) {
+                return Err(DocumentError::InvalidNonce);
+            }
+            if self.document_owner_get(document_id)? != owner {
+                return Err(DocumentError::NotOwner);
+            }
+            let payload = self.permit_payload(owner, spender, document_id, deadline, nonce);
+            // sr25519 account ids are the public key of the account
+            self.env()
+                .sr25519_verify(&signature, &payload, owner.as_ref())
+                .map_err(|_| DocumentError::InvalidSignature)?;
+            self.approve_for(&owner, &spender, document_id)?;
+            self.permit_nonces
+                .insert(owner, &nonce.checked_add(1).expect("Overflow"));
+            Ok(())
+        }
+        // get the nonce the next permit of the owner has to be signed with
+        #[ink(message)]
+        pub fn permit_nonce(&self, owner: AccountId) -> u64 {
+            self.permit_nonces.get(owner).unwrap_or(0)
+        }
+        // get the domain separator of this contract instance
+        #[ink(message)]
+        pub fn permit_domain_separator(&self) -> Hash {
+            let domain = (PERMIT_DOMAIN, self.env().account_id());
+            Hash::from(self.env().hash_encoded::<Blake2x256, _>(&domain))
+        }
+        // get the SCALE encoded payload the owner has to sign for a permit
+        #[ink(message)]
+        pub fn permit_payload(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+            document_id: DocumentId,
+            deadline: BlockNumber,
+            nonce: u64,
+        ) -> Vec<u8> {
+            (
+                self.permit_domain_separator(),
+                owner,
+                spender,
+                document_id,
+                deadline,
+                nonce,
+            )
+                .encode()
+        }
+        // set the price and terms the owner sell licenses of the document for
+        #[ink(message)]
+        pub fn set_license_terms(
+            &mut self,
+            document_id: DocumentId,
+            price: Balance,
+            terms_hash: Hash,
+            duration: Option<BlockNumber>,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::All)?;
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, document_id)?;
+            let terms = LicenseTerms {
+                price,
+                terms_hash,
+                duration,
+            };
+            self.license_terms.insert(document_id, &terms);
+            self.env().emit_event(LicenseTermsUpdated {
+                id: document_id,
+                terms: Some(terms),
+            });
+            Ok(())
+        }
+        // stop selling licenses of the document, bought licenses stay valid
+        #[ink(message)]
+        pub fn remove_license_terms(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::All)?;
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, document_id)?;
+            if self.license_terms.take(document_id).is_none() {
+                return Err(DocumentError::NoDataFound);
+            }
+            self.env().emit_event(LicenseTermsUpdated {
+                id: document_id,
+                terms: None,
+            });
+            Ok(())
+        }
+        // get the license terms of the document
+        #[ink(message)]
+        pub fn license_terms_get(&self, document_id: DocumentId) -> DocumentResult<LicenseTerms> {
+            self.license_terms
+                .get(document_id)
+                .ok_or(DocumentError::NoDataFound)
+        }
+        // buy a license of the document, the transferred value has to match the price
+        // and is paid to the document owner. A running license is extended by the duration
+        // of the terms, a perpetual one cannot be bought again
+        #[ink(message, payable)]
+        pub fn purchase_license(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::All)?;
+            let caller = self.env().caller();
+            let owner = self.document_owner_get(document_id)?;
+            if caller == owner {
+                return Err(DocumentError::NotAllow);
+            }
+            let terms = self.license_terms_get(document_id)?;
+            if self.env().transferred_value() != terms.price {
+                return Err(DocumentError::IncorrectPayment);
+            }
+            let purchased_at = self.env().block_number();
+            let current = self.licenses.get((caller, document_id));
+            if current.is_none() {
+                self.index_licensee(&caller, document_id);
+            }
+            let expires_at = match current {
+                Some(current)
+                    if current
+                        .expires_at
+                        .is_none_or(|expires_at| purchased_at < expires_at) =>
+                {
+                    match (current.expires_at, terms.duration) {
+                        (None, _) => return Err(DocumentError::DuplicationData),
+                        (Some(expires_at), Some(duration)) => {
+                            Some(expires_at.saturating_add(duration))
+                        }
+                        (Some(_), None) => None,
+                    }
+                }
+                _ => terms
+                    .duration
+                    .map(|duration| purchased_at.saturating_add(duration)),
+            };
+            let license = License {
+                terms_hash: terms.terms_hash,
+                price: terms.price,
+                purchased_at,
+                expires_at,
+            };
+            self.licenses.insert((caller, document_id), &license);
+            // a license is no sale, the owner keep the whole price
+            self.pay(&owner, terms.price)?;
+            self.env().emit_event(LicensePurchased {
+                id: document_id,
+                buyer: caller,
+                price: license.price,
+                expires_at: license.expires_at,
+            });
+            Ok(())
+        }
+        // get the license an account bought for the document
+        #[ink(message)]
+        pub fn license_get(
+            &self,
+            account: AccountId,
+            document_id: DocumentId,
+        ) -> DocumentResult<License> {
+            self.licenses
+                .get((account, document_id))
+                .ok_or(DocumentError::NoDataFound)
+        }
+        // check if the account may access the document, the owner and the co-owners always can
+        #[ink(message)]
+        pub fn has_license(&self, account: AccountId, document_id: DocumentId) -> bool {
+            if self.document_owned_by(account, document_id)
+                || self.is_co_owner(account, document_id)
+            {
+                return true;
+            }
+            if self.user_of(document_id) == Some(account) {
+                return true;
+            }
+            match self.licenses.get((account, document_id)) {
+                Some(license) => license
+                    .expires_at
+                    .is_none_or(|expires_at| self.env().block_number() < expires_at),
+                None => false,
+            }
+        }
+        // put the document up for sale, a listed document can only change hands by `buy_document`
+        #[ink(message)]
+        pub fn list_document(
+            &mut self,
+            document_id: DocumentId,
+            price: Balance,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::All)?;
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, document_id)?;
+            self.listings.insert(
+                document_id,
+                &Listing {
+                    seller: caller,
+                    price,
+                },
+            );
+            self.env().emit_event(DocumentListed {
+                id: document_id,
+                seller: caller,
+                price,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn cancel_listing(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, document_id)?;
+            if self.listings.take(document_id).is_none() {
+                return Err(DocumentError::NotListed);
+            }
+            self.env().emit_event(ListingCancelled {
+                id: document_id,
+                seller: caller,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn listing_get(&self, document_id: DocumentId) -> DocumentResult<Listing> {
+            self.listings
+                .get(document_id)
+                .ok_or(DocumentError::NotListed)
+        }
+        // buy a listed document, the transferred value has to match the listing price
+        #[ink(message, payable)]
+        pub fn buy_document(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            let listing = self.listing_get(document_id)?;
+            if caller == listing.seller {
+                return Err(DocumentError::NotAllow);
+            }
+            if self.env().transferred_value() != listing.price {
+                return Err(DocumentError::IncorrectPayment);
+            }
+            self.sell_document(&listing.seller, &caller, document_id, listing.price)
+        }
+        // escrow the transferred value as an offer for the document
+        #[ink(message, payable)]
+        pub fn make_offer(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::All)?;
+            let caller = self.env().caller();
+            if self.document_owner_get(document_id)? == caller {
+                return Err(DocumentError::NotAllow);
+            }
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(DocumentError::IncorrectPayment);
+            }
+            if self.offers.contains((document_id, caller)) {
+                return Err(DocumentError::DuplicationData);
+            }
+            self.offers.insert((document_id, caller), &amount);
+            let count = self.offer_counter.get(document_id).unwrap_or(0);
+            self.offer_buyers.insert((document_id, count), &caller);
+            self.offer_position.insert((document_id, caller), &count);
+            self.offer_counter
+                .insert(document_id, &count.checked_add(1).expect("Overflow"));
+            self.env().emit_event(OfferMade {
+                id: document_id,
+                buyer: caller,
+                amount,
+            });
+            Ok(())
+        }
+        // withdraw the offer of the caller and refund the escrowed value
+        #[ink(message)]
+        pub fn cancel_offer(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let caller = self.env().caller();
+            let amount = self
+                .take_offer(document_id, &caller)
+                .ok_or(DocumentError::OfferNotFound)?;
+            self.pay(&caller, amount)?;
+            self.env().emit_event(OfferCancelled {
+                id: document_id,
+                buyer: caller,
+                amount,
+            });
+            Ok(())
+        }
+        // sell the document to the buyer for the value the buyer escrowed
+        #[ink(message)]
+        pub fn accept_offer(
+            &mut self,
+            document_id: DocumentId,
+            buyer: AccountId,
+        ) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, document_id)?;
+            let amount = self
+                .take_offer(document_id, &buyer)
+                .ok_or(DocumentError::OfferNotFound)?;
+            self.sell_document(&caller, &buyer, document_id, amount)
+        }
+        #[ink(message)]
+        pub fn offer_get(
+            &self,
+            document_id: DocumentId,
+            buyer: AccountId,
+        ) -> DocumentResult<Balance> {
+            self.offers
+                .get((document_id, buyer))
+                .ok_or(DocumentError::OfferNotFound)
+        }
+        // rent the usage rights of the document to `user` until the `expires` block,
+        // the owner keep the ownership
+        #[ink(message)]
+        pub fn set_user(
+            &mut self,
+            document_id: DocumentId,
+            user: AccountId,
+            expires: BlockNumber,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::Approvals)?;
+            let caller = self.env().caller();
+            let owner = self.document_owner_get(document_id)?;
+            if !self.approved_or_owner(caller, owner, document_id)? {
+                return Err(DocumentError::NotAllow);
+            }
+            self.document_users
+                .insert(document_id, &DocumentUser { user, expires });
+            self.env().emit_event(UpdateUser {
+                id: document_id,
+                user: Some(user),
+                expires,
+            });
+            Ok(())
+        }
+        // get the current user of the document, none once the rental expired
+        #[ink(message)]
+        pub fn user_of(&self, document_id: DocumentId) -> Option<AccountId> {
+            self.document_users
+                .get(document_id)
+                .filter(|rental| self.env().block_number() < rental.expires)
+                .map(|rental| rental.user)
+        }
+        // get the block the rental of the document expire at, 0 when it is not rented
+        #[ink(message)]
+        pub fn user_expires(&self, document_id: DocumentId) -> BlockNumber {
+            self.document_users
+                .get(document_id)
+                .map(|rental| rental.expires)
+                .unwrap_or(0)
+        }
+        // store the document key encrypted to the public key of `recipient`
+        #[ink(message)]
+        pub fn key_envelope_add(
+            &mut self,
+            document_id: DocumentId,
+            recipient: AccountId,
+            envelope: Vec<u8>,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, document_id)?;
+            if envelope.len() > MAX_ENVELOPE_SIZE {
+                return Err(DocumentError::EnvelopeTooLarge);
+            }
+            let mut recipients = self.key_envelope_recipients(document_id);
+            if !recipients.contains(&recipient) {
+                if recipients.len() >= MAX_ENVELOPE_RECIPIENTS {
+                    return Err(DocumentError::TooManyRecipients);
+                }
+                recipients.push(recipient);
+                self.envelope_recipients.insert(document_id, &recipients);
+            }
+            let key_version = self.document_key_version_get(document_id);
+            self.key_envelopes.insert(
+                (document_id, recipient),
+                &KeyEnvelope {
+                    key_version,
+                    data: envelope,
+                },
+            );
+            self.env().emit_event(KeyEnvelopeAdded {
+                id: document_id,
+                recipient,
+                key_version,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn key_envelope_revoke(
+            &mut self,
+            document_id: DocumentId,
+            recipient: AccountId,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, document_id)?;
+            if self.key_envelopes.take((document_id, recipient)).is_none() {
+                return Err(DocumentError::NoDataFound);
+            }
+            let mut recipients = self.key_envelope_recipients(document_id);
+            recipients.retain(|account| *account != recipient);
+            self.envelope_recipients.insert(document_id, &recipients);
+            self.env().emit_event(KeyEnvelopeRevoked {
+                id: document_id,
+                recipient,
+            });
+            Ok(())
+        }
+        // get the key envelope of the document sealed for the caller
+        #[ink(message)]
+        pub fn key_envelope_get(&self, document_id: DocumentId) -> DocumentResult<KeyEnvelope> {
+            self.ensure_not_audited(document_id)?;
+            let caller = self.env().caller();
+            self.key_envelopes
+                .get((document_id, caller))
+                .ok_or(DocumentError::NoDataFound)
+        }
+        // in audited access mode the document has no plaintext location, the owner seal it in
+        // the key envelopes and those are only released by the key gateways after
+        // `document_access` recorded the access. A location already published cannot be taken back, it has to be
+        // removed before the mode can be turned on
+        #[ink(message)]
+        pub fn document_audited_access_set(
+            &mut self,
+            document_id: DocumentId,
+            enabled: bool,
+        ) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let caller = self.env().caller();
+            let record = self.owned_record(&caller, document_id)?;
+            if enabled && record.location.is_some() {
+                return Err(DocumentError::NotAllow);
+            }
+            if enabled {
+                self.audited_access.insert(document_id, &true);
+            } else {
+                self.audited_access.remove(document_id);
+            }
+            self.env().emit_event(AuditedAccessChanged {
+                id: document_id,
+                enabled,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn document_audited_access_get(&self, document_id: DocumentId) -> bool {
+            self.is_audited(document_id)
+        }
+        // record an access of the caller to the document, with the block and the `purpose`
+        // code, and return its position in the access log. Only the accounts the owner sealed
+        // a key envelope for can call it, and each record cost the deposit of one entry which
+        // stays with the contract as the log is never pruned.
+        // The envelope itself is not returned: key gateways release it only once they see the
+        // `DocumentAccessed` event, so a dry-run of this message gives nothing away
+        #[ink(message, payable)]
+        pub fn document_access(
+            &mut self,
+            document_id: DocumentId,
+            purpose: u32,
+        ) -> DocumentResult<u32> {
+            self.ensure_not_paused(PauseScope::All)?;
+            let caller = self.env().caller();
+            if !self.documents.contains(document_id) {
+                return Err(DocumentError::DocumentNotFound);
+            }
+            if !self.key_envelopes.contains((document_id, caller)) {
+                return Err(DocumentError::NoDataFound);
+            }
+            if self.env().transferred_value() != self.deposit_per_entry_get() {
+                return Err(DocumentError::IncorrectPayment);
+            }
+            let block = self.env().block_number();
+            let len = self.document_access_count(document_id);
+            self.access_log.insert(
+                (document_id, len),
+                &AccessRecord {
+                    accessor: caller,
+                    purpose,
+                    block,
+                },
+            );
+            self.access_log_len
+                .insert(document_id, &len.checked_add(1).expect("Overflow"));
+            self.env().emit_event(DocumentAccessed {
+                id: document_id,
+                accessor: caller,
+                purpose,
+                block,
+            });
+            Ok(len)
+        }
+        // access records of the document in the order they got made, owner only
+        #[ink(message)]
+        pub fn document_access_log(
+            &self,
+            document_id: DocumentId,
+            start: u32,
+            limit: u32,
+        ) -> DocumentResult<Vec<AccessRecord>> {
+            // reading the log is no action on the document, every co-owner can
+            let caller = self.env().caller();
+            if !self.document_owned_by(caller, document_id)
+                && !self.is_co_owner(caller, document_id)
+            {
+                return Err(DocumentError::NotOwner);
+            }
+            Ok(
+                page_range(self.document_access_count(document_id), start, limit)
+                    .filter_map(|index| self.access_log.get((document_id, index)))
+                    .collect(),
+            )
+        }
+        #[ink(message)]
+        pub fn document_access_count(&self, document_id: DocumentId) -> u32 {
+            self.access_log_len.get(document_id).unwrap_or(0)
+        }
+        #[ink(message)]
+        pub fn key_envelope_recipients(&self, document_id: DocumentId) -> Vec<AccountId> {
+            self.envelope_recipients
+                .get(document_id)
+                .unwrap_or_default()
+        }
+        // rotate the document key, `envelopes` has to hold a new envelope for every
+        // remaining recipient
+        #[ink(message)]
+        pub fn key_envelopes_rotate(
+            &mut self,
+            document_id: DocumentId,
+            envelopes: Vec<(AccountId, Vec<u8>)>,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, document_id)?;
+            let recipients = self.key_envelope_recipients(document_id);
+            if envelopes.len() != recipients.len() {
+                return Err(DocumentError::EnvelopeMismatch);
+            }
+            for (index, (recipient, envelope)) in envelopes.iter().enumerate() {
+                if !recipients.contains(recipient)
+                    || envelopes[..index].iter().any(|(seen, _)| seen == recipient)
+                {
+                    return Err(DocumentError::EnvelopeMismatch);
+                }
+                if envelope.len() > MAX_ENVELOPE_SIZE {
+                    return Err(DocumentError::EnvelopeTooLarge);
+                }
+            }
+            let key_version = self
+                .document_key_version_get(document_id)
+                .checked_add(1)
+                .expect("Overflow");
+            self.document_key_version.insert(document_id, &key_version);
+            for (recipient, data) in envelopes {
+                self.key_envelopes
+                    .insert((document_id, recipient), &KeyEnvelope { key_version, data });
+            }
+            self.env().emit_event(DocumentKeyRotated {
+                id: document_id,
+                key_version,
+                recipients: recipients.len() as u32,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn document_key_version_get(&self, document_id: DocumentId) -> u32 {
+            self.document_key_version.get(document_id).unwrap_or(0)
+        }
+        // link the source document to the target, only the owner of the source can do it.
+        // Superseding the document of another owner waits for that owner to accept it
+        #[ink(message)]
+        pub fn document_link_new(
+            &mut self,
+            source: DocumentId,
+            target: DocumentId,
+            kind: LinkKind,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, source)?;
+            if !self.documents.contains(target) {
+                return Err(DocumentError::DocumentNotFound);
+            }
+            if source == target {
+                return Err(DocumentError::LinkCycle);
+            }
+            if self.linked.contains((source, target, kind)) {
+                return Err(DocumentError::DuplicationData);
+            }
+            if kind == LinkKind::Supersedes {
+                self.ensure_supersedable(source, target)?;
+                if !self.document_owned_by(caller, target) {
+                    self.supersede_requests.insert(source, &target);
+                    self.env().emit_event(SupersedeRequested { source, target });
+                    return Ok(());
+                }
+                self.superseded_by.insert(target, &source);
+            }
+            self.insert_link(source, target, kind);
+            Ok(())
+        }
+        // accept the request of `source` to supersede a document of the caller
+        #[ink(message)]
+        pub fn document_supersede_accept(&mut self, source: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            let target = self
+                .supersede_requests
+                .get(source)
+                .ok_or(DocumentError::NoDataFound)?;
+            self.ensure_owner(&caller, target)?;
+            if !self.documents.contains(source) {
+                return Err(DocumentError::DocumentNotFound);
+            }
+            self.ensure_supersedable(source, target)?;
+            self.supersede_requests.remove(source);
+            self.superseded_by.insert(target, &source);
+            self.insert_link(source, target, LinkKind::Supersedes);
+            Ok(())
+        }
+        // drop the request of `source`, done by the owner of either document
+        #[ink(message)]
+        pub fn document_supersede_reject(&mut self, source: DocumentId) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            let target = self
+                .supersede_requests
+                .get(source)
+                .ok_or(DocumentError::NoDataFound)?;
+            if !(self.document_owned_by(caller, target) || self.document_owned_by(caller, source)) {
+                return Err(DocumentError::NotOwner);
+            }
+            self.supersede_requests.remove(source);
+            self.env().emit_event(SupersedeRejected { source, target });
+            Ok(())
+        }
+        // get the document `source` asked to supersede
+        #[ink(message)]
+        pub fn document_supersede_request_get(&self, source: DocumentId) -> Option<DocumentId> {
+            self.supersede_requests.get(source)
+        }
+        // get the links the document point to, paged by `start` and `limit`
+        #[ink(message)]
+        pub fn document_links_outgoing(
+            &self,
+            document_id: DocumentId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<DocumentLink> {
+            page_range(self.document_link_count_outgoing(document_id), start, limit)
+                .filter_map(|index| self.outgoing_links.get((document_id, index)))
+                .collect()
+        }
+        // get the links pointing to the document, paged by `start` and `limit`
+        #[ink(message)]
+        pub fn document_links_incoming(
+            &self,
+            document_id: DocumentId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<DocumentLink> {
+            page_range(self.document_link_count_incoming(document_id), start, limit)
+                .filter_map(|index| self.incoming_links.get((document_id, index)))
+                .collect()
+        }
+        #[ink(message)]
+        pub fn document_link_count_outgoing(&self, document_id: DocumentId) -> u32 {
+            self.outgoing_link_counter.get(document_id).unwrap_or(0)
+        }
+        #[ink(message)]
+        pub fn document_link_count_incoming(&self, document_id: DocumentId) -> u32 {
+            self.incoming_link_counter.get(document_id).unwrap_or(0)
+        }
+        // get the newer document that superseded this one
+        #[ink(message)]
+        pub fn document_superseded_by(&self, document_id: DocumentId) -> Option<DocumentId> {
+            self.superseded_by.get(document_id)
+        }
+        // name `author` as an author of the document, the author has to accept it
+        #[ink(message)]
+        pub fn author_nominate(
+            &mut self,
+            document_id: DocumentId,
+            author: AccountId,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_publisher(&caller, document_id)?;
+            self.nominate_author(&caller, document_id, author)
+        }
+        // withdraw a nomination the author did not accept yet. Accepted authors can only
+        // resign themselves, their credit doesn't depend on the current owner
+        #[ink(message)]
+        pub fn author_nomination_withdraw(
+            &mut self,
+            document_id: DocumentId,
+            author: AccountId,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.ensure_publisher(&caller, document_id)?;
+            self.remove_pending_author(&caller, document_id, author)
+        }
+        // accept the nomination of the caller
+        #[ink(message)]
+        pub fn author_accept(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            let mut pending = self.pending_authors.get(document_id).unwrap_or_default();
+            let Some(position) = pending.iter().position(|author| *author == caller) else {
+                return Err(DocumentError::NotAuthors);
+            };
+            pending.swap_remove(position);
+            self.pending_authors.insert(document_id, &pending);
+            self.confirm_author(document_id, caller);
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn author_decline(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            self.remove_pending_author(&caller, document_id, caller)
+        }
+        // remove the caller from the confirmed authors
+        #[ink(message)]
+        pub fn author_resign(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            let mut authors = self.document_authors(document_id);
+            let Some(position) = authors.iter().position(|author| *author == caller) else {
+                return Err(DocumentError::NotAuthors);
+            };
+            authors.remove(position);
+            self.document_authors.insert(document_id, &authors);
+            self.unindex_authored(&caller, document_id);
+            self.env().emit_event(AuthorRemoved {
+                id: document_id,
+                author: caller,
+                by: caller,
+            });
+            Ok(())
+        }
+        // confirmed authors of the document, in the order they accepted
+        #[ink(message)]
+        pub fn document_authors(&self, document_id: DocumentId) -> Vec<AccountId> {
+            self.document_authors.get(document_id).unwrap_or_default()
+        }
+        #[ink(message)]
+        pub fn document_pending_authors(&self, document_id: DocumentId) -> Vec<AccountId> {
+            self.pending_authors.get(document_id).unwrap_or_default()
+        }
+        // documents the account is a confirmed author of, paged by `start` and `limit`
+        #[ink(message)]
+        pub fn documents_authored_by(
+            &self,
+            author: AccountId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<DocumentId> {
+            page_range(self.document_authored_count(author), start, limit)
+                .filter_map(|index| self.authored_documents.get((author, index)))
+                .collect()
+        }
+        #[ink(message)]
+        pub fn document_authored_count(&self, author: AccountId) -> u32 {
+            self.authored_counter.get(author).unwrap_or(0)
+        }
+        // documents owned by the account, paged by `start` and `limit`. Co-owned documents
+        // are listed under the contract account holding them, not under their co-owners
+        #[ink(message)]
+        pub fn documents_owned_by(
+            &self,
+            owner: AccountId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<DocumentId> {
+            page_range(self.owned_index_len.get(owner).unwrap_or(0), start, limit)
+                .filter_map(|index| self.owned_documents.get((owner, index)))
+                .collect()
+        }
+        // name the guardians able to recover the documents of the caller, `threshold` of
+        // them have to approve and the caller can veto during `delay` blocks
+        #[ink(message)]
+        pub fn recovery_config_set(
+            &mut self,
+            guardians: Vec<AccountId>,
+            threshold: u32,
+            delay: BlockNumber,
+        ) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let caller = self.env().caller();
+            if self.recoveries.contains(caller) {
+                return Err(DocumentError::NotAllow);
+            }
+            if guardians.is_empty() || guardians.len() > MAX_GUARDIANS {
+                return Err(DocumentError::NotAllow);
+            }
+            for (index, guardian) in guardians.iter().enumerate() {
+                if *guardian == caller
+                    || *guardian == AccountId::from([0x0; 32])
+                    || guardians[..index].contains(guardian)
+                {
+                    return Err(DocumentError::NotAllow);
+                }
+            }
+            if threshold == 0 || threshold as usize > guardians.len() {
+                return Err(DocumentError::InvalidThreshold);
+            }
+            let config = RecoveryConfig {
+                guardians,
+                threshold,
+                delay,
+            };
+            self.recovery_configs.insert(caller, &config);
+            self.env().emit_event(RecoveryConfigured {
+                account: caller,
+                config: Some(config),
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn recovery_config_remove(&mut self) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let caller = self.env().caller();
+            if self.recoveries.contains(caller) {
+                return Err(DocumentError::NotAllow);
+            }
+            if self.recovery_configs.take(caller).is_none() {
+                return Err(DocumentError::NoDataFound);
+            }
+            self.env().emit_event(RecoveryConfigured {
+                account: caller,
+                config: None,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn recovery_config_get(&self, account: AccountId) -> Option<RecoveryConfig> {
+            self.recovery_configs.get(account)
+        }
+        #[ink(message)]
+        pub fn recovery_get(&self, lost: AccountId) -> Option<Recovery> {
+            self.recoveries.get(lost)
+        }
+        // a guardian start moving the documents of `lost` to `new_account`, counting as
+        // its approval
+        #[ink(message)]
+        pub fn recovery_initiate(
+            &mut self,
+            lost: AccountId,
+            new_account: AccountId,
+        ) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let caller = self.env().caller();
+            self.ensure_guardian(&caller, &lost)?;
+            if self.recoveries.contains(lost) {
+                return Err(DocumentError::DuplicationData);
+            }
+            if new_account == lost
+                || new_account == AccountId::from([0x0; 32])
+                || new_account == self.env().account_id()
+            {
+                return Err(DocumentError::NotAllow);
+            }
+            self.recoveries.insert(
+                lost,
+                &Recovery {
+                    new_account,
+                    approvals: Vec::new(),
+                    executable_at: None,
+                    moved: 0,
+                },
+            );
+            self.env().emit_event(RecoveryInitiated {
+                lost,
+                new_account,
+                by: caller,
+            });
+            self.approve_recovery(&caller, &lost)
+        }
+        #[ink(message)]
+        pub fn recovery_approve(&mut self, lost: AccountId) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let caller = self.env().caller();
+            self.ensure_guardian(&caller, &lost)?;
+            self.approve_recovery(&caller, &lost)
+        }
+        // the owner still holding its key stop the recovery, until documents got moved
+        #[ink(message)]
+        pub fn recovery_veto(&mut self) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let caller = self.env().caller();
+            let recovery = self
+                .recoveries
+                .get(caller)
+                .ok_or(DocumentError::NoDataFound)?;
+            if recovery.moved != 0 {
+                return Err(DocumentError::NotAllow);
+            }
+            self.recoveries.remove(caller);
+            self.env().emit_event(RecoveryVetoed { lost: caller });
+            Ok(())
+        }
+        // move the documents and co-ownerships of the lost account once the delay passed, a
+        // chunk per call. Returns true when the lost account owns and co-owns nothing anymore
+        #[ink(message)]
+        pub fn recovery_finalize(&mut self, lost: AccountId) -> DocumentResult<bool> {
+            self.ensure_not_paused(PauseScope::Transfers)?;
+            let mut recovery = self
+                .recoveries
+                .get(lost)
+                .ok_or(DocumentError::NoDataFound)?;
+            match recovery.executable_at {
+                Some(at) if self.env().block_number() >= at => {}
+                _ => return Err(DocumentError::RecoveryNotReady),
+            }
+            let new_account = recovery.new_account;
+            // the documents and co-ownerships stay with the same person, the quota of the new
+            // account is not checked
+            for _ in 0..MIGRATION_CHUNK_SIZE {
+                if let Some(last) = self.owned_index_len.get(lost).unwrap_or(0).checked_sub(1) {
+                    let document_id = self
+                        .owned_documents
+                        .get((lost, last))
+                        .ok_or(DocumentError::CannotFetchValue)?;
+                    // the approval deposit goes back to the same person, under the new account
+                    let mut record = self.record(document_id)?;
+                    if record.approved.take().is_some() {
+                        self.refund_deposit_entry(&mut record, &new_account)?;
+                        self.documents.insert(document_id, &record);
+                    }
+                    self.move_document(&lost, &new_account, document_id)?;
+                } else if let Some(last) = self
+                    .co_owned_index_len
+                    .get(lost)
+                    .unwrap_or(0)
+                    .checked_sub(1)
+                {
+                    let document_id = self
+                        .co_owned_documents
+                        .get((lost, last))
+                        .ok_or(DocumentError::CannotFetchValue)?;
+                    self.move_co_ownership(&lost, &new_account, document_id)?;
+                } else {
+                    break;
+                }
+                recovery.moved = recovery.moved.checked_add(1).expect("Overflow");
+            }
+            if self.owned_index_len.get(lost).unwrap_or(0) != 0
+                || self.co_owned_index_len.get(lost).unwrap_or(0) != 0
+            {
+                self.recoveries.insert(lost, &recovery);
+                return Ok(false);
+            }
+            self.recoveries.remove(lost);
+            self.env().emit_event(RecoveryFinalized {
+                lost,
+                new_account,
+                moved: recovery.moved,
+            });
+            Ok(true)
+        }
+        // deliver the current content of the document to `to`, who has to acknowledge it
+        // until `deadline`. The delivery is kept in the history of the document and the
+        // recipient, it cost the deposit of one entry which stays with the contract.
+        // Returns the delivery id
+        #[ink(message, payable)]
+        pub fn mark_delivered(
+            &mut self,
+            document_id: DocumentId,
+            to: AccountId,
+            deadline: Option<BlockNumber>,
+        ) -> DocumentResult<u32> {
+            self.ensure_not_paused(PauseScope::All)?;
+            let caller = self.env().caller();
+            if !self.document_owned_by(caller, document_id) {
+                return Err(DocumentError::NotOwner);
+            }
+            let revision = self
+                .record(document_id)?
+                .content
+                .ok_or(DocumentError::NoDataFound)?;
+            if to == caller || to == AccountId::from([0x0; 32]) {
+                return Err(DocumentError::NotAllow);
+            }
+            let block = self.env().block_number();
+            if deadline.is_some_and(|deadline| deadline < block) {
+                return Err(DocumentError::DeadlinePassed);
+            }
+            // a recipient has a single pending delivery per document
+            if let Some(pending) = self.pending_deliveries.get((document_id, to)) {
+                if !self.expire_delivery(document_id, pending)? {
+                    return Err(DocumentError::DuplicationData);
+                }
+            }
+            if self.env().transferred_value() != self.deposit_per_entry_get() {
+                return Err(DocumentError::IncorrectPayment);
+            }
+            let delivery_id = self.delivery_count(document_id);
+            self.deliveries.insert(
+                (document_id, delivery_id),
+                &Delivery {
+                    sender: caller,
+                    recipient: to,
+                    revision,
+                    delivered_at: block,
+                    deadline,
+                    status: DeliveryStatus::Pending,
+                    settled_at: None,
+                },
+            );
+            self.delivery_counter
+                .insert(document_id, &delivery_id.checked_add(1).expect("Overflow"));
+            self.pending_deliveries
+                .insert((document_id, to), &delivery_id);
+            let received = self.received_count(to);
+            self.received_deliveries
+                .insert((to, received), &(document_id, delivery_id));
+            self.received_position
+                .insert((document_id, delivery_id), &received);
+            self.received_counter
+                .insert(to, &received.checked_add(1).expect("Overflow"));
+            self.env().emit_event(DocumentDelivered {
+                id: document_id,
+                sender: caller,
+                recipient: to,
+                delivery_id,
+                revision,
+                deadline,
+            });
+            Ok(delivery_id)
+        }
+        // the recipient confirm it got the delivered `revision` of the document
+        #[ink(message)]
+        pub fn acknowledge(
+            &mut self,
+            document_id: DocumentId,
+            revision: Hash,
+        ) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let caller = self.env().caller();
+            let delivery_id = self
+                .pending_deliveries
+                .get((document_id, caller))
+                .ok_or(DocumentError::NoDataFound)?;
+            let mut delivery = self
+                .deliveries
+                .get((document_id, delivery_id))
+                .ok_or(DocumentError::NoDataFound)?;
+            let block = self.env().block_number();
+            if delivery.deadline.is_some_and(|deadline| block > deadline) {
+                return Err(DocumentError::DeadlinePassed);
+            }
+            if delivery.revision != revision {
+                return Err(DocumentError::WrongRevision);
+            }
+            delivery.status = DeliveryStatus::Acknowledged;
+            delivery.settled_at = Some(block);
+            self.deliveries
+                .insert((document_id, delivery_id), &delivery);
+            self.pending_deliveries.remove((document_id, caller));
+            self.env().emit_event(DeliveryAcknowledged {
+                id: document_id,
+                recipient: caller,
+                delivery_id,
+                revision,
+                block,
+            });
+            Ok(())
+        }
+        // close the pending delivery of `recipient` once its deadline passed, anyone can
+        #[ink(message)]
+        pub fn delivery_expire(
+            &mut self,
+            document_id: DocumentId,
+            recipient: AccountId,
+        ) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let delivery_id = self
+                .pending_deliveries
+                .get((document_id, recipient))
+                .ok_or(DocumentError::NoDataFound)?;
+            if !self.expire_delivery(document_id, delivery_id)? {
+                return Err(DocumentError::NotAllow);
+            }
+            Ok(())
+        }
+        // the sender withdraw the pending delivery of `recipient`, a delivery without a
+        // deadline otherwise blocks the next one
+        #[ink(message)]
+        pub fn delivery_cancel(
+            &mut self,
+            document_id: DocumentId,
+            recipient: AccountId,
+        ) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            let delivery_id = self
+                .pending_deliveries
+                .get((document_id, recipient))
+                .ok_or(DocumentError::NoDataFound)?;
+            let mut delivery = self
+                .deliveries
+                .get((document_id, delivery_id))
+                .ok_or(DocumentError::NoDataFound)?;
+            if delivery.sender != self.env().caller() {
+                return Err(DocumentError::NotAllow);
+            }
+            let block = self.env().block_number();
+            delivery.status = DeliveryStatus::Cancelled;
+            delivery.settled_at = Some(block);
+            self.deliveries
+                .insert((document_id, delivery_id), &delivery);
+            self.pending_deliveries.remove((document_id, recipient));
+            self.env().emit_event(DeliveryCancelled {
+                id: document_id,
+                recipient,
+                delivery_id,
+                block,
+            });
+            Ok(())
+        }
+        // a pending delivery past its deadline reads as expired
+        #[ink(message)]
+        pub fn delivery_get(&self, document_id: DocumentId, delivery_id: u32) -> Option<Delivery> {
+            self.deliveries
+                .get((document_id, delivery_id))
+                .map(|delivery| self.delivery_view(delivery))
+        }
+        // deliveries of the document in the order they got made
+        #[ink(message)]
+        pub fn document_deliveries(
+            &self,
+            document_id: DocumentId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<Delivery> {
+            page_range(self.delivery_count(document_id), start, limit)
+                .filter_map(|delivery_id| self.delivery_get(document_id, delivery_id))
+                .collect()
+        }
+        #[ink(message)]
+        pub fn delivery_count(&self, document_id: DocumentId) -> u32 {
+            self.delivery_counter.get(document_id).unwrap_or(0)
+        }
+        // deliveries made to the account with the document and delivery id
+        #[ink(message)]
+        pub fn deliveries_to(
+            &self,
+            recipient: AccountId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<(DocumentId, u32, Delivery)> {
+            page_range(self.received_count(recipient), start, limit)
+                .filter_map(|index| self.received_deliveries.get((recipient, index)))
+                .filter_map(|(document_id, delivery_id)| {
+                    self.delivery_get(document_id, delivery_id)
+                        .map(|delivery| (document_id, delivery_id, delivery))
+                })
+                .collect()
+        }
+        #[ink(message)]
+        pub fn received_count(&self, recipient: AccountId) -> u32 {
+            self.received_counter.get(recipient).unwrap_or(0)
+        }
+        // turn the caller's document into a template, its first version is 1. `content` has to
+        // be the current content of the document
+        #[ink(message)]
+        pub fn template_register(
+            &mut self,
+            document_id: DocumentId,
+            content: Hash,
+            schema: Hash,
+        ) -> DocumentResult<u32> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            let caller = self.env().caller();
+            if self.owned_record(&caller, document_id)?.content != Some(content) {
+                return Err(DocumentError::WrongRevision);
+            }
+            if self.backed_templates.contains(document_id) {
+                return Err(DocumentError::DuplicationData);
+            }
+            let template_id = self.template_counter.get().unwrap_or(0);
+            self.template_counter
+                .set(&template_id.checked_add(1).expect("Overflow"));
+            self.backed_templates.insert(document_id, &template_id);
+            self.templates.insert(
+                template_id,
+                &Template {
+                    document_id,
+                    latest_version: 0,
+                },
+            );
+            self.env().emit_event(TemplateRegistered {
+                template_id,
+                document_id,
+                owner: caller,
+            });
+            self.add_template_version(template_id, content, schema)?;
+            Ok(template_id)
+        }
+        // publish a new version of the template, new documents are instantiated from it
+        #[ink(message)]
+        pub fn template_version_new(
+            &mut self,
+            template_id: u32,
+            content: Hash,
+            schema: Hash,
+        ) -> DocumentResult<u32> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            // a version describe the current content of the template document
+            if self.ensure_template_owner(template_id)?.content != Some(content) {
+                return Err(DocumentError::WrongRevision);
+            }
+            self.add_template_version(template_id, content, schema)
+        }
+        // documents already instantiated from the version keep it
+        #[ink(message)]
+        pub fn template_version_deprecate(
+            &mut self,
+            template_id: u32,
+            version: u32,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::ContentWrites)?;
+            self.ensure_template_owner(template_id)?;
+            let mut template_version = self
+                .template_versions
+                .get((template_id, version))
+                .ok_or(DocumentError::TemplateNotFound)?;
+            if template_version.deprecated {
+                return Err(DocumentError::TemplateDeprecated);
+            }
+            template_version.deprecated = true;
+            self.template_versions
+                .insert((template_id, version), &template_version);
+            self.env().emit_event(TemplateVersionDeprecated {
+                template_id,
+                version,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn template_get(&self, template_id: u32) -> Option<Template> {
+            self.templates.get(template_id)
+        }
+        #[ink(message)]
+        pub fn template_version_get(
+            &self,
+            template_id: u32,
+            version: u32,
+        ) -> Option<TemplateVersion> {
+            self.template_versions.get((template_id, version))
+        }
+        // mint a document for the caller from the latest version of the template, linked
+        // to the template document. Returns the id allocated by the contract
+        #[ink(message, payable)]
+        pub fn document_from_template(
+            &mut self,
+            template_id: u32,
+            field_values_hash: Hash,
+        ) -> DocumentResult<DocumentId> {
+            let caller = self.env().caller();
+            let template = self
+                .templates
+                .get(template_id)
+                .ok_or(DocumentError::TemplateNotFound)?;
+            let version = template.latest_version;
+            let template_version = self
+                .template_versions
+                .get((template_id, version))
+                .ok_or(DocumentError::TemplateNotFound)?;
+            if template_version.deprecated {
+                return Err(DocumentError::TemplateDeprecated);
+            }
+            if !self.documents.contains(template.document_id) {
+                return Err(DocumentError::DocumentNotFound);
+            }
+            let document_id = self.allocate_document_id();
+            self.mint_document(&caller, document_id)?;
+            self.document_templates.insert(
+                document_id,
+                &TemplateUse {
+                    template_id,
+                    version,
+                    field_values: field_values_hash,
+                },
+            );
+            self.insert_link(document_id, template.document_id, LinkKind::DerivedFrom);
+            self.env().emit_event(DocumentFromTemplate {
+                id: document_id,
+                template_id,
+                version,
+                field_values: field_values_hash,
+            });
+            Ok(document_id)
+        }
+        // template version the document got instantiated from
+        #[ink(message)]
+        pub fn document_template_get(&self, document_id: DocumentId) -> Option<TemplateUse> {
+            self.document_templates.get(document_id)
+        }
+        // share the ownership of the caller's document with `owners`. The document move to
+        // the custody of the contract account, `document_owner_get` and `document_owned_by`
+        // see that account and `is_co_owner` tells the co-owners
+        #[ink(message)]
+        pub fn co_ownership_create(
+            &mut self,
+            document_id: DocumentId,
+            owners: Vec<AccountId>,
+            threshold: u32,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::Transfers)?;
+            let caller = self.env().caller();
+            self.ensure_owner(&caller, document_id)?;
+            if self.listings.contains(document_id) {
+                return Err(DocumentError::DocumentIsListed);
+            }
+            if owners.is_empty() || owners.len() > MAX_CO_OWNERS {
+                return Err(DocumentError::NotAllow);
+            }
+            let custody = self.env().account_id();
+            for (index, owner) in owners.iter().enumerate() {
+                if *owner == custody
+                    || *owner == AccountId::from([0x0; 32])
+                    || owners[..index].contains(owner)
+                {
+                    return Err(DocumentError::NotAllow);
+                }
+            }
+            if threshold == 0 || threshold as usize > owners.len() {
+                return Err(DocumentError::InvalidThreshold);
+            }
+            self.move_document(&caller, &custody, document_id)?;
+            // licenses would pay the custody account, no co-owner could get the price out
+            if self.license_terms.take(document_id).is_some() {
+                self.env().emit_event(LicenseTermsUpdated {
+                    id: document_id,
+                    terms: None,
+                });
+            }
+            for owner in owners.iter() {
+                self.index_co_owned(owner, document_id);
+            }
+            self.co_ownerships.insert(
+                document_id,
+                &CoOwnership {
+                    owners: owners.clone(),
+                    threshold,
+                    creator: caller,
+                    first_proposal: self.proposal_counter.get(document_id).unwrap_or(0),
+                },
+            );
+            self.env().emit_event(CoOwnershipCreated {
+                id: document_id,
+                creator: caller,
+                owners,
+                threshold,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn co_ownership_get(&self, document_id: DocumentId) -> Option<CoOwnership> {
+            self.co_ownerships.get(document_id)
+        }
+        // propose an action on a co-owned document, confirmed by the proposer. The proposer
+        // pays the deposit of one entry for the stored proposal, unless its confirmation
+        // already reach the threshold. The call reaching the threshold executes the action
+        // and pays the deposit of a new entry
+        #[ink(message, payable)]
+        pub fn proposal_new(
+            &mut self,
+            document_id: DocumentId,
+            action: CoOwnerAction,
+            duration: BlockNumber,
+        ) -> DocumentResult<u32> {
+            let caller = self.env().caller();
+            self.ensure_not_paused(PauseScope::All)?;
+            self.ensure_co_owner(&caller, document_id)?;
+            let threshold = self
+                .co_ownership_get(document_id)
+                .map_or(u32::MAX, |co_ownership| co_ownership.threshold);
+            // a proposal executed right away is never stored
+            let deposit = if threshold > 1 {
+                self.deposit_per_entry_get()
+            } else {
+                0
+            };
+            if self.env().transferred_value() != deposit {
+                return Err(DocumentError::IncorrectPayment);
+            }
+            let proposal_id = self.proposal_counter.get(document_id).unwrap_or(0);
+            self.proposal_counter
+                .insert(document_id, &proposal_id.checked_add(1).expect("Overflow"));
+            let expires_at = self.env().block_number().saturating_add(duration);
+            self.proposals.insert(
+                (document_id, proposal_id),
+                &Proposal {
+                    action: action.clone(),
+                    proposer: caller,
+                    confirmations: Vec::new(),
+                    expires_at,
+                    deposit,
+                },
+            );
+            self.env().emit_event(ProposalCreated {
+                id: document_id,
+                proposal_id,
+                proposer: caller,
+                action,
+                expires_at,
+            });
+            self.confirm_proposal(&caller, document_id, proposal_id, deposit)?;
+            Ok(proposal_id)
+        }
+        #[ink(message, payable)]
+        pub fn proposal_confirm(
+            &mut self,
+            document_id: DocumentId,
+            proposal_id: u32,
+        ) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            self.ensure_not_paused(PauseScope::All)?;
+            self.ensure_co_owner(&caller, document_id)?;
+            self.confirm_proposal(&caller, document_id, proposal_id, 0)
+        }
+        // withdraw the confirmation of the caller
+        #[ink(message)]
+        pub fn proposal_revoke(
+            &mut self,
+            document_id: DocumentId,
+            proposal_id: u32,
+        ) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            self.ensure_not_migrating()?;
+            let mut proposal = self.proposal_get(document_id, proposal_id)?;
+            let Some(position) = proposal
+                .confirmations
+                .iter()
+                .position(|account| *account == caller)
+            else {
+                return Err(DocumentError::NoDataFound);
+            };
+            proposal.confirmations.swap_remove(position);
+            self.proposals.insert((document_id, proposal_id), &proposal);
+            self.env().emit_event(ProposalRevoked {
+                id: document_id,
+                proposal_id,
+                by: caller,
+            });
+            Ok(())
+        }
+        // drop the proposal and refund its deposit, only the proposer can. Expired proposals
+        // and those of an ended co-ownership can be cancelled too
+        #[ink(message)]
+        pub fn proposal_cancel(
+            &mut self,
+            document_id: DocumentId,
+            proposal_id: u32,
+        ) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            self.ensure_not_migrating()?;
+            let proposal = self
+                .proposals
+                .get((document_id, proposal_id))
+                .ok_or(DocumentError::NoDataFound)?;
+            if proposal.proposer != caller {
+                return Err(DocumentError::NotAllow);
+            }
+            self.proposals.remove((document_id, proposal_id));
+            self.pay(&caller, proposal.deposit)?;
+            self.env().emit_event(ProposalCancelled {
+                id: document_id,
+                proposal_id,
+            });
+            Ok(())
+        }
+        // get an open proposal of the current co-ownership of the document
+        #[ink(message)]
+        pub fn proposal_get(
+            &self,
+            document_id: DocumentId,
+            proposal_id: u32,
+        ) -> DocumentResult<Proposal> {
+            let co_ownership = self
+                .co_ownerships
+                .get(document_id)
+                .ok_or(DocumentError::NoDataFound)?;
+            if proposal_id < co_ownership.first_proposal {
+                return Err(DocumentError::NoDataFound);
+            }
+            self.proposals
+                .get((document_id, proposal_id))
+                .ok_or(DocumentError::NoDataFound)
+        }
+        // set the deposit charged per stored entry, deposits already held are unaffected
+        #[ink(message)]
+        pub fn set_deposit_per_entry(&mut self, amount: Balance) -> DocumentResult<()> {
+            self.ensure_admin()?;
+            self.deposit_per_entry.set(&amount);
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn deposit_per_entry_get(&self) -> Balance {
+            self.deposit_per_entry.get().unwrap_or(0)
+        }
+        // get the storage deposit held for the document
+        #[ink(message)]
+        pub fn document_deposit_get(&self, document_id: DocumentId) -> Balance {
+            self.documents
+                .get(document_id)
+                .map(|record| record.deposit.amount)
+                .unwrap_or(0)
+        }
+        // set the number of documents every account may hold, `None` remove the limit
+        #[ink(message)]
+        pub fn set_default_quota(&mut self, limit: Option<u32>) -> DocumentResult<()> {
+            self.ensure_admin()?;
+            self.default_quota.set(&limit);
+            Ok(())
+        }
+        // override the default quota of an account, `None` fall back to the default
+        #[ink(message)]
+        pub fn set_account_quota(
+            &mut self,
+            account: AccountId,
+            limit: Option<u32>,
+        ) -> DocumentResult<()> {
+            self.ensure_admin()?;
+            match limit {
+                Some(limit) => {
+                    self.account_quotas.insert(account, &limit);
+                }
+                None => self.account_quotas.remove(account),
+            }
+            Ok(())
+        }
+        // get the number of documents the account hold against its limit
+        #[ink(message)]
+        pub fn document_quota_get(&self, account: AccountId) -> QuotaUsage {
+            QuotaUsage {
+                used: self.numof_owned_documents(account),
+                limit: self
+                    .account_quotas
+                    .get(account)
+                    .or(self.default_quota.get().flatten()),
+            }
+        }
+        // stop the operations of the scope until the admin unpause them
+        #[ink(message)]
+        pub fn pause(&mut self, scope: PauseScope) -> DocumentResult<()> {
+            self.ensure_admin()?;
+            self.paused.insert(scope, &true);
+            self.env().emit_event(Paused {
+                scope,
+                by: self.env().caller(),
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn unpause(&mut self, scope: PauseScope) -> DocumentResult<()> {
+            self.ensure_admin()?;
+            self.paused.remove(scope);
+            self.env().emit_event(Unpaused {
+                scope,
+                by: self.env().caller(),
+            });
+            Ok(())
+        }
+        // check if the operations of the scope are stopped, directly or by the global pause
+        #[ink(message)]
+        pub fn is_paused(&self, scope: PauseScope) -> bool {
+            self.paused.contains(PauseScope::All) || self.paused.contains(scope)
+        }
+        #[ink(message)]
+        pub fn admin_get(&self) -> Option<AccountId> {
+            self.admin.get()
+        }
+        #[ink(message)]
+        pub fn transfer_admin(&mut self, new_admin: AccountId) -> DocumentResult<()> {
+            self.ensure_admin()?;
+            self.admin.set(&new_admin);
+            Ok(())
+        }
+        // an instance deployed before the admin existed never ran `new`, so neither the
+        // admin nor the upgrade authority is set and `migrate` cannot run. The upgrade
+        // authority, or while it is unset the account that upgraded the code, claims both
+        // once. Batch the call with the code upgrade so nobody else can claim in between
+        #[ink(message)]
+        pub fn claim_admin(&mut self) -> DocumentResult<()> {
+            let caller = self.env().caller();
+            if self.admin.get().is_some() {
+                return Err(DocumentError::NotAllow);
+            }
+            match self.upgrade_authority_get() {
+                Some(authority) if authority != caller => {
+                    return Err(DocumentError::NotUpgradeAuthority)
+                }
+                Some(_) => {}
+                None => self.upgrade_authority.set(&caller),
+            }
+            self.admin.set(&caller);
+            Ok(())
+        }
+        // define a named retention policy, a policy cannot be redefined once assigned
+        #[ink(message)]
+        pub fn retention_policy_new(
+            &mut self,
+            name: Hash,
+            min_blocks: BlockNumber,
+            max_blocks: Option<BlockNumber>,
+        ) -> DocumentResult<()> {
+            self.ensure_admin()?;
+            if max_blocks.is_some_and(|max_blocks| max_blocks < min_blocks) {
+                return Err(DocumentError::InvalidRetention);
+            }
+            if self.retention_policies.contains(name) {
+                return Err(DocumentError::DuplicationData);
+            }
+            self.retention_policies.insert(
+                name,
+                &RetentionPolicy {
+                    min_blocks,
+                    max_blocks,
+                },
+            );
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn retention_policy_get(&self, name: Hash) -> DocumentResult<RetentionPolicy> {
+            self.retention_policies
+                .get(name)
+                .ok_or(DocumentError::NoDataFound)
+        }
+        // put the document under a retention policy, counted from the current block
+        #[ink(message)]
+        pub fn document_retention_assign(
+            &mut self,
+            document_id: DocumentId,
+            policy: Hash,
+        ) -> DocumentResult<()> {
+            self.ensure_admin()?;
+            if !self.documents.contains(document_id) {
+                return Err(DocumentError::DocumentNotFound);
+            }
+            let retention_policy = self.retention_policy_get(policy)?;
+            let now = self.env().block_number();
+            let retention = DocumentRetention {
+                policy,
+                retain_until: now.saturating_add(retention_policy.min_blocks),
+                expires_at: retention_policy
+                    .max_blocks
+                    .map(|max_blocks| now.saturating_add(max_blocks)),
+            };
+            self.document_retention.insert(document_id, &retention);
+            self.env().emit_event(RetentionAssigned {
+                id: document_id,
+                policy,
+                retain_until: retention.retain_until,
+                expires_at: retention.expires_at,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn document_retention_get(
+            &self,
+            document_id: DocumentId,
+        ) -> DocumentResult<DocumentRetention> {
+            self.document_retention
+                .get(document_id)
+                .ok_or(DocumentError::NoDataFound)
+        }
+        // destroy a document past the maximum retention of its policy, anyone can do it
+        #[ink(message)]
+        pub fn purge_expired(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::All)?;
+            let record = self.record(document_id)?;
+            let owner = record.owner;
+            let expires_at = self
+                .document_retention_get(document_id)?
+                .expires_at
+                .ok_or(DocumentError::NotExpired)?;
+            if self.env().block_number() < expires_at {
+                return Err(DocumentError::NotExpired);
+            }
+            self.clear_document(document_id, record)?;
+            self.env().emit_event(DocumentPurged {
+                id: document_id,
+                owner,
+                purged_by: self.env().caller(),
+            });
+            Ok(())
+        }
+        // get the royalty receiver and the royalty owed for a sale at `sale_price`, a
+        // document without royalty owes nothing to its owner like EIP-2981 does
+        #[ink(message)]
+        pub fn royalty_info(
+            &self,
+            document_id: DocumentId,
+            sale_price: Balance,
+        ) -> DocumentResult<(AccountId, Balance)> {
+            let owner = self.document_owner_get(document_id)?;
+            Ok(match self.royalties.get(document_id) {
+                Some(royalty) => (
+                    royalty.receiver,
+                    royalty_amount(sale_price, royalty.basis_points),
+                ),
+                None => (owner, 0),
+            })
+        }
+        // get the attestations of a document, paged by `start` and `limit`
+        #[ink(message)]
+        pub fn document_attestations_get(
+            &self,
+            document_id: DocumentId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<Attestation> {
+            page_range(self.document_attestation_count(document_id), start, limit)
+                .filter_map(|index| self.document_attestations.get((document_id, index)))
+                .collect()
+        }
+        // get number of attestations stored for a document
+        #[ink(message)]
+        pub fn document_attestation_count(&self, document_id: DocumentId) -> u32 {
+            self.attestation_counter.get(document_id).unwrap_or(0)
+        }
+        // first phase of an upgrade, record the code hash that can be applied after the delay
+        #[ink(message)]
+        pub fn propose_upgrade(&mut self, code_hash: Hash) -> DocumentResult<()> {
+            let caller = self.ensure_upgrade_authority()?;
+            if self.pending_upgrade_get().is_some() {
+                return Err(DocumentError::DuplicationData);
+            }
+            let executable_at = self
+                .env()
+                .block_number()
+                .saturating_add(self.upgrade_delay_get());
+            self.pending_upgrade.set(&Some(PendingUpgrade {
+                code_hash,
+                executable_at,
+            }));
+            self.env().emit_event(UpgradeProposed {
+                code_hash,
+                by: caller,
+                executable_at,
+            });
+            Ok(())
+        }
+        // second phase of an upgrade, switch to the proposed code once the delay passed
+        #[ink(message)]
+        pub fn execute_upgrade(&mut self) -> DocumentResult<()> {
+            let caller = self.ensure_upgrade_authority()?;
+            let pending = self
+                .pending_upgrade_get()
+                .ok_or(DocumentError::NoDataFound)?;
+            if self.env().block_number() < pending.executable_at {
+                return Err(DocumentError::UpgradeNotReady);
+            }
+            self.pending_upgrade.set(&None);
+            self.env()
+                .set_code_hash(&pending.code_hash)
+                .map_err(|_| DocumentError::UpgradeFailed)?;
+            self.env().emit_event(UpgradeExecuted {
+                code_hash: pending.code_hash,
+                by: caller,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn cancel_upgrade(&mut self) -> DocumentResult<()> {
+            let caller = self.ensure_upgrade_authority()?;
+            let pending = self
+                .pending_upgrade_get()
+                .ok_or(DocumentError::NoDataFound)?;
+            self.pending_upgrade.set(&None);
+            self.env().emit_event(UpgradeCancelled {
+                code_hash: pending.code_hash,
+                by: caller,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn pending_upgrade_get(&self) -> Option<PendingUpgrade> {
+            self.pending_upgrade.get().flatten()
+        }
+        // change the delay of the next proposals, not while a proposal is pending
+        #[ink(message)]
+        pub fn set_upgrade_delay(&mut self, delay: BlockNumber) -> DocumentResult<()> {
+            self.ensure_upgrade_authority()?;
+            if self.pending_upgrade_get().is_some() {
+                return Err(DocumentError::NotAllow);
+            }
+            let old_delay = self.upgrade_delay_get();
+            // a longer delay applies at once, a shorter one only after the current delay
+            // so users still have the time to react to the change itself
+            let effective_at = if delay >= old_delay {
+                self.pending_upgrade_delay.set(&None);
+                self.upgrade_delay.set(&delay);
+                self.env().block_number()
+            } else {
+                let effective_at = self.env().block_number().saturating_add(old_delay);
+                self.upgrade_delay.set(&old_delay);
+                self.pending_upgrade_delay.set(&Some(PendingDelay {
+                    delay,
+                    effective_at,
+                }));
+                effective_at
+            };
+            self.env().emit_event(UpgradeDelayChanged {
+                old_delay,
+                new_delay: delay,
+                effective_at,
+            });
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn upgrade_delay_get(&self) -> BlockNumber {
+            if let Some(pending) = self.pending_upgrade_delay_get() {
+                if self.env().block_number() >= pending.effective_at {
+                    return pending.delay;
+                }
+            }
+            self.upgrade_delay.get().unwrap_or(DEFAULT_UPGRADE_DELAY)
+        }
+        #[ink(message)]
+        pub fn pending_upgrade_delay_get(&self) -> Option<PendingDelay> {
+            self.pending_upgrade_delay.get().flatten()
+        }
+        #[ink(message)]
+        pub fn upgrade_authority_get(&self) -> Option<AccountId> {
+            self.upgrade_authority.get()
+        }
+        #[ink(message)]
+        pub fn transfer_upgrade_authority(
+            &mut self,
+            new_authority: AccountId,
+        ) -> DocumentResult<()> {
+            self.ensure_upgrade_authority()?;
+            self.upgrade_authority.set(&new_authority);
+            Ok(())
+        }
+        // check minting and content writes against a `DocumentAcounts` instance, `None` let
+        // every account write again
+        #[ink(message)]
+        pub fn set_access_requirement(
+            &mut self,
+            requirement: Option<AccessRequirement>,
+        ) -> DocumentResult<()> {
+            self.ensure_admin()?;
+            self.access_requirement.set(&requirement);
+            Ok(())
+        }
+        #[ink(message)]
+        pub fn access_requirement_get(&self) -> Option<AccessRequirement> {
+            self.access_requirement.get().flatten()
+        }
+        #[ink(message)]
+        pub fn layout_version_get(&self) -> u32 {
+            self.layout_version.get().unwrap_or(0)
+        }
+        #[ink(message)]
+        pub fn migration_get(&self) -> Option<MigrationState> {
+            self.migration.get().flatten()
+        }
+        // migrate the stored data from layout version `from` to `to = from + 1`, one chunk of
+        // documents per call. Returns true once the migration is finished, calling it again
+        // for an applied version is a no-op
+        #[ink(message)]
+        pub fn migrate(&mut self, from: u32, to: u32) -> DocumentResult<bool> {
+            self.ensure_admin()?;
+            if from.checked_add(1) != Some(to) || to > CURRENT_LAYOUT_VERSION {
+                return Err(DocumentError::WrongLayoutVersion);
+            }
+            let version = self.layout_version_get();
+            if version >= to {
+                return Ok(true);
+            }
+            if version != from {
+                return Err(DocumentError::WrongLayoutVersion);
+            }
+            let mut state = self.migration_get().unwrap_or(MigrationState {
+                from,
+                to,
+                cursor: 0,
+            });
+            state.cursor = self.migrate_chunk(to, state.cursor);
+            if state.cursor < self.document_total() {
+                self.migration.set(&Some(state));
+                return Ok(false);
+            }
+            self.migration.set(&None);
+            self.layout_version.set(&to);
+            self.env().emit_event(Migrated { from, to });
+            Ok(true)
+        }
+        // index the documents of an instance deployed before the document index existed,
+        // has to run before `migrate(0, 1)`. Returns the number of documents added
+        #[ink(message)]
+        pub fn document_index_backfill(
+            &mut self,
+            document_ids: Vec<DocumentId>,
+        ) -> DocumentResult<u32> {
+            self.ensure_admin()?;
+            if self.layout_version_get() != 0 || document_ids.len() > MIGRATION_CHUNK_SIZE as usize
+            {
+                return Err(DocumentError::NotAllow);
+            }
+            let mut added = 0u32;
+            for document_id in document_ids {
+                if self.legacy_owner.contains(document_id)
+                    && !self.document_index_position.contains(document_id)
+                {
+                    self.index_document(document_id);
+                    added += 1;
+                }
+            }
+            Ok(added)
+        }
+        // number of existing documents
+        #[ink(message)]
+        pub fn document_total(&self) -> u32 {
+            self.document_index_len.get().unwrap_or(0)
+        }
+        #[ink(message)]
+        pub fn document_by_index(&self, index: u32) -> Option<DocumentId> {
+            self.document_index.get(index)
+        }
+
+        ///Helper function
+        // apply the step to layout version `to` on the next chunk of the document index,
+        // returns the cursor to continue from
+        fn migrate_chunk(&mut self, to: u32, cursor: u32) -> u32 {
+            let end = cursor
+                .saturating_add(MIGRATION_CHUNK_SIZE)
+                .min(self.document_total());
+            for position in cursor..end {
+                if let Some(document_id) = self.document_index.get(position) {
+                    self.migrate_document(to, document_id);
+                }
+            }
+            end
+        }
+        fn migrate_document(&mut self, to: u32, document_id: DocumentId) {
+            match to {
+                // version 1 only added the document index, filled by `document_index_backfill`
+                1 => {}
+                // version 2 moved the content off the prefix it shared with the owned
+                // document counter
+                2 => {
+                    let legacy_key = (DOCUMENT_COUNTER_KEY, document_id);
+                    if let Ok(Some(content)) =
+                        ink::env::get_contract_storage::<_, Hash>(&legacy_key)
+                    {
+                        self.legacy_content.insert(document_id, &content);
+                        ink::env::clear_contract_storage(&legacy_key);
+                    }
+                }
+                // version 3 packed the fields of a document into a single record
+                3 => {
+                    let Some(owner) = self.legacy_owner.take(document_id) else {
+                        return;
+                    };
+                    let record = DocumentRecord {
+                        owner,
+                        content: self.legacy_content.take(document_id),
+                        metadata: self.legacy_metadata.take(document_id),
+                        location: self.legacy_location.take(document_id),
+                        approved: self.legacy_approvals.take(document_id),
+                        deposit: self.legacy_deposits.take(document_id).unwrap_or_default(),
+                    };
+                    self.documents.insert(document_id, &record);
+                }
+                // version 4 added the owner index
+                4 => {
+                    if self.owned_position.contains(document_id) {
+                        return;
+                    }
+                    if let Some(record) = self.documents.get(document_id) {
+                        self.index_owned(&record.owner, document_id);
+                    }
+                }
+                _ => unreachable!("no migration to layout version {}, {}", to, document_id),
+            }
+        }
+        fn index_document(&mut self, document_id: DocumentId) {
+            let len = self.document_total();
+            self.document_index.insert(len, &document_id);
+            self.document_index_position.insert(document_id, &len);
+            self.document_index_len
+                .set(&len.checked_add(1).expect("Overflow"));
+        }
+        // swap the last entry into the place of the removed document
+        fn unindex_document(&mut self, document_id: DocumentId) {
+            let Some(position) = self.document_index_position.take(document_id) else {
+                return;
+            };
+            let last = self.document_total().saturating_sub(1);
+            if position != last {
+                if let Some(moved) = self.document_index.get(last) {
+                    self.document_index.insert(position, &moved);
+                    self.document_index_position.insert(moved, &position);
+                }
+            }
+            self.document_index.remove(last);
+            self.document_index_len.set(&last);
+        }
+        fn index_owned(&mut self, owner: &AccountId, document_id: DocumentId) {
+            let len = self.owned_index_len.get(owner).unwrap_or(0);
+            self.owned_documents.insert((*owner, len), &document_id);
+            self.owned_position.insert(document_id, &len);
+            self.owned_index_len
+                .insert(owner, &len.checked_add(1).expect("Overflow"));
+        }
+        // swap the last document of the owner into the place of the removed one
+        fn unindex_owned(&mut self, owner: &AccountId, document_id: DocumentId) {
+            let Some(position) = self.owned_position.take(document_id) else {
+                return;
+            };
+            let last = self
+                .owned_index_len
+                .get(owner)
+                .unwrap_or(0)
+                .saturating_sub(1);
+            if position != last {
+                if let Some(moved) = self.owned_documents.get((*owner, last)) {
+                    self.owned_documents.insert((*owner, position), &moved);
+                    self.owned_position.insert(moved, &position);
+                }
+            }
+            self.owned_documents.remove((*owner, last));
+            self.owned_index_len.insert(owner, &last);
+        }
+        fn index_co_owned(&mut self, account: &AccountId, document_id: DocumentId) {
+            let len = self.co_owned_index_len.get(account).unwrap_or(0);
+            self.co_owned_documents
+                .insert((*account, len), &document_id);
+            self.co_owned_position.insert((*account, document_id), &len);
+            self.co_owned_index_len
+                .insert(account, &len.checked_add(1).expect("Overflow"));
+        }
+        fn unindex_co_owned(&mut self, account: &AccountId, document_id: DocumentId) {
+            let Some(position) = self.co_owned_position.take((*account, document_id)) else {
+                return;
+            };
+            let last = self
+                .co_owned_index_len
+                .get(account)
+                .unwrap_or(0)
+                .saturating_sub(1);
+            if position != last {
+                if let Some(moved) = self.co_owned_documents.get((*account, last)) {
+                    self.co_owned_documents.insert((*account, position), &moved);
+                    self.co_owned_position.insert((*account, moved), &position);
+                }
+            }
+            self.co_owned_documents.remove((*account, last));
+            self.co_owned_index_len.insert(account, &last);
+        }
+        fn end_co_ownership(&mut self, document_id: DocumentId) {
+            if let Some(co_ownership) = self.co_ownerships.take(document_id) {
+                for owner in co_ownership.owners.iter() {
+                    self.unindex_co_owned(owner, document_id);
+                }
+            }
+        }
+        // put the new account in place of the lost one, as the creator too when the lost
+        // account put the document in custody
+        fn move_co_ownership(
+            &mut self,
+            lost: &AccountId,
+            new_account: &AccountId,
+            document_id: DocumentId,
+        ) -> DocumentResult<()> {
+            let mut co_ownership = self
+                .co_ownerships
+                .get(document_id)
+                .ok_or(DocumentError::CannotFetchValue)?;
+            self.unindex_co_owned(lost, document_id);
+            if co_ownership.owners.contains(new_account) {
+                // the same person cannot count twice, the threshold follows the owners left
+                co_ownership.owners.retain(|owner| owner != lost);
+                co_ownership.threshold =
+                    co_ownership.threshold.min(co_ownership.owners.len() as u32);
+            } else {
+                for owner in co_ownership.owners.iter_mut() {
+                    if owner == lost {
+                        *owner = *new_account;
+                    }
+                }
+                self.index_co_owned(new_account, document_id);
+            }
+            if co_ownership.creator == *lost {
+                co_ownership.creator = *new_account;
+            }
+            self.co_ownerships.insert(document_id, &co_ownership);
+            Ok(())
+        }
+        // ask the configured accounts contract whether `account` may mint and write content
+        fn ensure_registered_writer(&self, account: &AccountId) -> DocumentResult<()> {
+            let Some(requirement) = self.access_requirement_get() else {
+                return Ok(());
+            };
+            let has_identity = build_call::<ink::env::DefaultEnvironment>()
+                .call(requirement.accounts)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "account_has_identity"
+                    )))
+                    .push_arg(account),
+                )
+                .returns::<bool>()
+                .try_invoke();
+            match has_identity {
+                Ok(Ok(true)) => {}
+                Ok(Ok(false)) => return Err(DocumentError::IdentityNotRegistered),
+                _ => return Err(DocumentError::AccountsCallFailed),
+            }
+            let Some(role) = requirement.role else {
+                return Ok(());
+            };
+            let has_role = build_call::<ink::env::DefaultEnvironment>()
+                .call(requirement.accounts)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("account_has_role")))
+                        .push_arg(account)
+                        .push_arg(role),
+                )
+                .returns::<bool>()
+                .try_invoke();
+            match has_role {
+                Ok(Ok(true)) => Ok(()),
+                Ok(Ok(false)) => Err(DocumentError::MissingRole),
+                _ => Err(DocumentError::AccountsCallFailed),
+            }
+        }
+        // state changes wait until the stored data is on the current layout version
+        fn ensure_not_migrating(&self) -> DocumentResult<()> {
+            if self.layout_version_get() != CURRENT_LAYOUT_VERSION {
+                return Err(DocumentError::MigrationInProgress);
+            }
+            Ok(())
+        }
+        // the transferred value has to match the deposit for one more entry
+        fn charge_deposit(&self, record: &mut DocumentRecord) -> DocumentResult<()> {
+            let amount = self.deposit_per_entry_get();
+            if self.env().transferred_value() != amount {
+                return Err(DocumentError::IncorrectPayment);
+            }
+            let deposit = &mut record.deposit;
+            deposit.entries = deposit.entries.checked_add(1).expect("Overflow");
+            deposit.amount = deposit.amount.checked_add(amount).expect("Overflow");
+            Ok(())
+        }
+        // refund the share of one entry to the current owner, the last entry take the rest
+        fn refund_deposit_entry(
+            &self,
+            record: &mut DocumentRecord,
+            owner: &AccountId,
+        ) -> DocumentResult<()> {
+            let deposit = &mut record.deposit;
+            if deposit.entries == 0 {
+                return Ok(());
+            }
+            let refund = deposit.amount / Balance::from(deposit.entries);
+            deposit.entries -= 1;
+            deposit.amount = deposit
+                .amount
+                .checked_sub(refund)
+                .expect("Cannot be negative");
+            if deposit.entries == 0 {
+                let rest = core::mem::take(&mut deposit.amount);
+                self.pay(owner, refund.checked_add(rest).expect("Overflow"))
+            } else {
+                self.pay(owner, refund)
+            }
+        }
+        fn ensure_within_quota(&self, account: &AccountId) -> DocumentResult<()> {
+            let usage = self.document_quota_get(*account);
+            if usage.limit.is_some_and(|limit| usage.used >= limit) {
+                return Err(DocumentError::QuotaExceeded);
+            }
+            Ok(())
+        }
+        fn ensure_not_paused(&self, scope: PauseScope) -> DocumentResult<()> {
+            self.ensure_not_migrating()?;
+            if self.is_paused(scope) {
+                return Err(DocumentError::OperationPaused);
+            }
+            Ok(())
+        }
+        fn ensure_upgrade_authority(&self) -> DocumentResult<AccountId> {
+            let caller = self.env().caller();
+            if self.upgrade_authority_get() != Some(caller) {
+                return Err(DocumentError::NotUpgradeAuthority);
+            }
+            Ok(caller)
+        }
+        fn ensure_admin(&self) -> DocumentResult<()> {
+            if self.admin.get() != Some(self.env().caller()) {
+                return Err(DocumentError::NotAdmin);
+            }
+            Ok(())
+        }
+        // remove the document and everything stored about it, shared by burn and purge.
+        // Escrowed offers and proposal deposits go back to their payers
+        fn clear_document(
+            &mut self,
+            document_id: DocumentId,
+            record: DocumentRecord,
+        ) -> DocumentResult<()> {
+            let owner = &record.owner;
+            self.decrease_documents_count(owner)?;
+            self.unindex_owned(owner, document_id);
+            self.documents.remove(document_id);
+            self.unindex_document(document_id);
+            self.listings.remove(document_id);
+            self.license_terms.remove(document_id);
+            self.royalties.remove(document_id);
+            self.document_retention.remove(document_id);
+            self.clear_user(document_id);
+            self.clear_key_envelopes(document_id);
+            self.clear_authors(document_id);
+            self.clear_licenses(document_id);
+            self.clear_links(document_id);
+            self.clear_attestations(document_id);
+            self.clear_deliveries(document_id);
+            self.clear_access_log(document_id);
+            self.clear_template(document_id);
+            self.clear_offers(document_id)?;
+            self.clear_proposals(document_id)?;
+            self.audited_access.remove(document_id);
+            self.document_templates.remove(document_id);
+            let payee = self.deposit_payee(owner, document_id);
+            self.end_co_ownership(document_id);
+            self.pay(&payee, record.deposit.amount)?;
+            self.env().emit_event(Burned {
+                id: document_id,
+                owner: *owner,
+                by: self.env().caller(),
+                content: record.content,
+                block: self.env().block_number(),
+            });
+            Ok(())
+        }
+        fn mint_document(&mut self, to: &AccountId, document_id: DocumentId) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::Minting)?;
+            self.ensure_registered_writer(to)?;
+            self.ensure_within_quota(to)?;
+            self.add_document_to(to, document_id)?;
+            self.increase_documents_count(to);
+            self.index_owned(to, document_id);
+            self.env().emit_event(OwnershipTransferred {
+                id: document_id,
+                from: None,
+                to: Some(*to),
+                block: self.env().block_number(),
+            });
+            Ok(())
+        }
+        fn ensure_owner(
+            &self,
+            caller: &AccountId,
+            document_id: DocumentId,
+        ) -> DocumentResult<AccountId> {
+            let owner = self.document_owner_get(document_id)?;
+            if owner != *caller {
+                return Err(DocumentError::NotOwner);
+            }
+            Ok(owner)
+        }
+        fn record(&self, document_id: DocumentId) -> DocumentResult<DocumentRecord> {
+            self.documents
+                .get(document_id)
+                .ok_or(DocumentError::DocumentNotFound)
+        }
+        // load the record of a document the caller has to own
+        fn owned_record(
+            &self,
+            caller: &AccountId,
+            document_id: DocumentId,
+        ) -> DocumentResult<DocumentRecord> {
+            let record = self.record(document_id)?;
+            if record.owner != *caller {
+                return Err(DocumentError::NotOwner);
+            }
+            Ok(record)
+        }
+        fn pay(&self, to: &AccountId, amount: Balance) -> DocumentResult<()> {
+            if amount == 0 {
+                return Ok(());
+            }
+            self.env()
+                .transfer(*to, amount)
+                .map_err(|_| DocumentError::TransferFailed)
+        }
+        // pay the royalty out of the price and the remainder to the seller
+        fn pay_with_royalty(
+            &self,
+            seller: &AccountId,
+            document_id: DocumentId,
+            price: Balance,
+        ) -> DocumentResult<()> {
+            let mut remainder = price;
+            if let Some(royalty) = self.royalties.get(document_id) {
+                let amount = royalty_amount(price, royalty.basis_points);
+                if royalty.receiver != *seller && amount > 0 {
+                    self.pay(&royalty.receiver, amount)?;
+                    remainder = price.checked_sub(amount).expect("Cannot be negative");
+                    self.env().emit_event(RoyaltyPaid {
+                        id: document_id,
+                        receiver: royalty.receiver,
+                        amount,
+                    });
+                }
+            }
+            self.pay(seller, remainder)
+        }
+        fn clear_user(&mut self, document_id: DocumentId) {
+            if self.document_users.take(document_id).is_some() {
+                self.env().emit_event(UpdateUser {
+                    id: document_id,
+                    user: None,
+                    expires: 0,
+                });
+            }
+        }
+        // the owner of the template document owns the template
+        // load the record of the template document the caller has to own
+        fn ensure_template_owner(&self, template_id: u32) -> DocumentResult<DocumentRecord> {
+            let template = self
+                .templates
+                .get(template_id)
+                .ok_or(DocumentError::TemplateNotFound)?;
+            self.owned_record(&self.env().caller(), template.document_id)
+        }
+        fn add_template_version(
+            &mut self,
+            template_id: u32,
+            content: Hash,
+            schema: Hash,
+        ) -> DocumentResult<u32> {
+            let mut template = self
+                .templates
+                .get(template_id)
+                .ok_or(DocumentError::TemplateNotFound)?;
+            let version = template.latest_version.checked_add(1).expect("Overflow");
+            template.latest_version = version;
+            self.templates.insert(template_id, &template);
+            self.template_versions.insert(
+                (template_id, version),
+                &TemplateVersion {
+                    content,
+                    schema,
+                    deprecated: false,
+                    registered_at: self.env().block_number(),
+                },
+            );
+            self.env().emit_event(TemplateVersionAdded {
+                template_id,
+                version,
+                content,
+                schema,
+            });
+            Ok(version)
+        }
+        // the callers pick any id for their documents, the ids they already took are skipped
+        fn allocate_document_id(&mut self) -> DocumentId {
+            let mut document_id = self.next_document_id.get().unwrap_or_default();
+            while self.documents.contains(document_id) {
+                document_id = document_id.checked_add(1).expect("Overflow");
+            }
+            self.next_document_id
+                .set(&document_id.checked_add(1).expect("Overflow"));
+            document_id
+        }
+        // store the link in both directions, the caller checked it
+        fn insert_link(&mut self, source: DocumentId, target: DocumentId, kind: LinkKind) {
+            let link = DocumentLink {
+                source,
+                target,
+                kind,
+            };
+            let outgoing = self.document_link_count_outgoing(source);
+            self.outgoing_links.insert((source, outgoing), &link);
+            self.outgoing_link_counter
+                .insert(source, &outgoing.checked_add(1).expect("Overflow"));
+            let incoming = self.document_link_count_incoming(target);
+            self.incoming_links.insert((target, incoming), &link);
+            self.incoming_link_counter
+                .insert(target, &incoming.checked_add(1).expect("Overflow"));
+            self.linked
+                .insert((source, target, kind), &LinkPosition { outgoing, incoming });
+            self.env().emit_event(DocumentLinked {
+                source,
+                target,
+                kind,
+            });
+        }
+        fn delivery_view(&self, mut delivery: Delivery) -> Delivery {
+            let block = self.env().block_number();
+            if delivery.status == DeliveryStatus::Pending
+                && delivery.deadline.is_some_and(|deadline| block > deadline)
+            {
+                delivery.status = DeliveryStatus::Expired;
+            }
+            delivery
+        }
+        // store the expiry of a pending delivery past its deadline, false if it isn't due
+        fn expire_delivery(
+            &mut self,
+            document_id: DocumentId,
+            delivery_id: u32,
+        ) -> DocumentResult<bool> {
+            let mut delivery = self
+                .deliveries
+                .get((document_id, delivery_id))
+                .ok_or(DocumentError::NoDataFound)?;
+            let block = self.env().block_number();
+            if delivery.deadline.is_none_or(|deadline| block <= deadline) {
+                return Ok(false);
+            }
+            delivery.status = DeliveryStatus::Expired;
+            delivery.settled_at = Some(block);
+            self.deliveries
+                .insert((document_id, delivery_id), &delivery);
+            self.pending_deliveries
+                .remove((document_id, delivery.recipient));
+            self.env().emit_event(DeliveryExpired {
+                id: document_id,
+                recipient: delivery.recipient,
+                delivery_id,
+                block,
+            });
+            Ok(true)
+        }
+        fn is_audited(&self, document_id: DocumentId) -> bool {
+            self.audited_access.get(document_id).unwrap_or(false)
+        }
+        fn ensure_not_audited(&self, document_id: DocumentId) -> DocumentResult<()> {
+            if self.is_audited(document_id) {
+                return Err(DocumentError::AuditedAccessRequired);
+            }
+            Ok(())
+        }
+        fn ensure_guardian(&self, account: &AccountId, lost: &AccountId) -> DocumentResult<()> {
+            let is_guardian = self
+                .recovery_configs
+                .get(lost)
+                .is_some_and(|config| config.guardians.contains(account));
+            if !is_guardian {
+                return Err(DocumentError::NotGuardian);
+            }
+            Ok(())
+        }
+        // the delay starts when the approvals reach the threshold
+        fn approve_recovery(&mut self, by: &AccountId, lost: &AccountId) -> DocumentResult<()> {
+            let mut recovery = self
+                .recoveries
+                .get(lost)
+                .ok_or(DocumentError::NoDataFound)?;
+            let config = self
+                .recovery_configs
+                .get(lost)
+                .ok_or(DocumentError::NoDataFound)?;
+            if recovery.approvals.contains(by) {
+                return Err(DocumentError::DuplicationData);
+            }
+            recovery.approvals.push(*by);
+            let approvals = recovery.approvals.len() as u32;
+            if approvals >= config.threshold && recovery.executable_at.is_none() {
+                recovery.executable_at =
+                    Some(self.env().block_number().saturating_add(config.delay));
+            }
+            self.recoveries.insert(lost, &recovery);
+            self.env().emit_event(RecoveryApproved {
+                lost: *lost,
+                by: *by,
+                approvals,
+                executable_at: recovery.executable_at,
+            });
+            Ok(())
+        }
+        fn ensure_co_owner(
+            &self,
+            account: &AccountId,
+            document_id: DocumentId,
+        ) -> DocumentResult<()> {
+            if !self.is_co_owner(*account, document_id) {
+                return Err(DocumentError::NotCoOwner);
+            }
+            Ok(())
+        }
+        // add the confirmation and execute the proposal once it reach the threshold, below it
+        // the call only pays `deposit`
+        fn confirm_proposal(
+            &mut self,
+            by: &AccountId,
+            document_id: DocumentId,
+            proposal_id: u32,
+            deposit: Balance,
+        ) -> DocumentResult<()> {
+            let mut proposal = self.proposal_get(document_id, proposal_id)?;
+            if self.env().block_number() > proposal.expires_at {
+                return Err(DocumentError::ProposalExpired);
+            }
+            if proposal.confirmations.contains(by) {
+                return Err(DocumentError::DuplicationData);
+            }
+            proposal.confirmations.push(*by);
+            let confirmations = proposal.confirmations.len() as u32;
+            self.env().emit_event(ProposalConfirmed {
+                id: document_id,
+                proposal_id,
+                by: *by,
+                confirmations,
+            });
+            let threshold = self
+                .co_ownership_get(document_id)
+                .map_or(u32::MAX, |co_ownership| co_ownership.threshold);
+            if confirmations < threshold {
+                // only the deposit of a new proposal is paid before the threshold
+                if self.env().transferred_value() != deposit {
+                    return Err(DocumentError::IncorrectPayment);
+                }
+                self.proposals.insert((document_id, proposal_id), &proposal);
+                return Ok(());
+            }
+            self.proposals.remove((document_id, proposal_id));
+            self.execute_proposal(by, document_id, proposal.action)?;
+            self.pay(&proposal.proposer, proposal.deposit)?;
+            self.env().emit_event(ProposalExecuted {
+                id: document_id,
+                proposal_id,
+                by: *by,
+            });
+            Ok(())
+        }
+        // run the action as the contract account holding the document
+        fn execute_proposal(
+            &mut self,
+            by: &AccountId,
+            document_id: DocumentId,
+            action: CoOwnerAction,
+        ) -> DocumentResult<()> {
+            let custody = self.env().account_id();
+            let mut record = self.record(document_id)?;
+            match action {
+                CoOwnerAction::Transfer { to } => {
+                    self.ensure_no_payment()?;
+                    self.ensure_not_paused(PauseScope::Transfers)?;
+                    if to == custody {
+                        return Err(DocumentError::NotAllow);
+                    }
+                    self.ensure_within_quota(&to)?;
+                    self.move_document(&custody, &to, document_id)
+                }
+                CoOwnerAction::Burn => {
+                    self.ensure_no_payment()?;
+                    self.ensure_not_paused(PauseScope::Transfers)?;
+                    self.ensure_burnable(document_id)?;
+                    self.clear_document(document_id, record)
+                }
+                CoOwnerAction::ReviseContent { content } => {
+                    self.ensure_not_paused(PauseScope::ContentWrites)?;
+                    self.ensure_registered_writer(by)?;
+                    let previous = record.content;
+                    match previous {
+                        Some(_) => self.ensure_no_payment()?,
+                        None => self.charge_deposit(&mut record)?,
+                    }
+                    record.content = Some(content);
+                    self.documents.insert(document_id, &record);
+                    self.env().emit_event(ContentAnchored {
+                        id: document_id,
+                        by: *by,
+                        previous,
+                        content: Some(content),
+                        block: self.env().block_number(),
+                    });
+                    Ok(())
+                }
+                CoOwnerAction::Approve { to } => {
+                    self.ensure_not_paused(PauseScope::Approvals)?;
+                    if to == AccountId::from([0x0; 32]) || record.approved.is_some() {
+                        return Err(DocumentError::CannotInsert);
+                    }
+                    self.charge_deposit(&mut record)?;
+                    record.approved = Some(to);
+                    self.documents.insert(document_id, &record);
+                    self.env().emit_event(Approval {
+                        from: custody,
+                        to,
                         id: document_id,
                     });
                     Ok(())
                 }
-                None => Err(DocumentError::DocumentNotFound),
             }
         }
-        //get the document owner
-        #[ink(message)]
-        pub fn document_owner_get(&self, document_id: DocumentId) -> DocumentResult<AccountId> {
-            match self.document_owner.get(document_id) {
-                Some(owner) => Ok(owner),
-                None => Err(DocumentError::DocumentNotFound),
+        fn ensure_no_payment(&self) -> DocumentResult<()> {
+            if self.env().transferred_value() != 0 {
+                return Err(DocumentError::IncorrectPayment);
             }
+            Ok(())
         }
-        //create a new content for the document
-        #[ink(message)]
-        pub fn document_content_new(
-            &mut self,
-            document_id: DocumentId,
-            cont: Hash,
-        ) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            if !self.document_owner.contains(document_id) {
-                return Err(DocumentError::DocumentNotFound);
-            }
-
-            match self.document_content.try_get(document_id) {
-                Some(_) => Err(DocumentError::DocumentIdAlreadyExists),
-                None => {
-                    self.document_content.insert(document_id, &cont);
-                    self.env().emit_event(DocumentUpdated {
-                        action: UpdateMessage::OwnverUpdate,
-                        from: caller,
-                        id: document_id,
-                    });
-                    Ok(())
+        // deposits of a co-owned document are refunded to the co-owner that created the
+        // co-ownership rather than to the contract account holding it
+        fn deposit_payee(&self, owner: &AccountId, document_id: DocumentId) -> AccountId {
+            if *owner == self.env().account_id() {
+                if let Some(co_ownership) = self.co_ownerships.get(document_id) {
+                    return co_ownership.creator;
                 }
             }
+            *owner
         }
-        //get document content
-        #[ink(message)]
-        pub fn document_content_get(&self, document_id: DocumentId) -> Option<Hash> {
-            self.document_content.get(&document_id).clone()
+        fn ensure_burnable(&self, document_id: DocumentId) -> DocumentResult<()> {
+            if self.listings.contains(document_id) {
+                return Err(DocumentError::DocumentIsListed);
+            }
+            self.ensure_not_retained(document_id)
         }
-        //check if document content exist
-        #[ink(message)]
-        pub fn verify_document_content(&self, document_id: DocumentId) -> bool {
-            self.document_content.contains(&document_id)
+        // a retained document keep its data until its minimum retention passed
+        fn ensure_not_retained(&self, document_id: DocumentId) -> DocumentResult<()> {
+            if let Some(retention) = self.document_retention.get(document_id) {
+                if self.env().block_number() < retention.retain_until {
+                    return Err(DocumentError::RetentionActive);
+                }
+            }
+            Ok(())
         }
-        #[ink(message)]
-        pub fn remove_document_content(&mut self, document_id: DocumentId) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            match self.check_owner_owned_document(&caller, &document_id) {
-                true => Ok(self.document_content.remove(document_id)),
-                false => Err(DocumentError::NotOwner),
+        // only the owner publish the document and name its authors
+        fn ensure_publisher(
+            &self,
+            caller: &AccountId,
+            document_id: DocumentId,
+        ) -> DocumentResult<()> {
+            if self.document_owner_get(document_id)? != *caller {
+                return Err(DocumentError::NotPublisher);
             }
+            Ok(())
         }
-        // add a new IPFS addr to the corresponding document
-        #[ink(message)]
-        pub fn document_addr_new(
+        fn nominate_author(
             &mut self,
+            by: &AccountId,
             document_id: DocumentId,
-            ipfs_addr: IPFSaddr,
+            author: AccountId,
         ) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            if self.check_owner_owned_document(&caller, &document_id) == false {
-                return Err(DocumentError::NotOwner);
+            let mut pending = self.document_pending_authors(document_id);
+            let authors = self.document_authors(document_id);
+            if pending.contains(&author) || authors.contains(&author) {
+                return Err(DocumentError::DuplicationData);
             }
-            match self.document_location.try_get(document_id) {
-                Some(_) => Err(DocumentError::DocumentIdAlreadyExists),
-                None => {
-                    let _insert = self.document_location.insert(document_id, &ipfs_addr);
-                    self.env().emit_event(DocumentUpdated {
-                        action: UpdateMessage::ContentUpdate,
-                        from: caller,
-                        id: document_id,
-                    });
-                    Ok(())
+            if pending.len() + authors.len() >= MAX_AUTHORS {
+                return Err(DocumentError::TooManyAuthors);
+            }
+            // naming yourself needs no consent
+            if author == *by {
+                self.confirm_author(document_id, author);
+                return Ok(());
+            }
+            pending.push(author);
+            self.pending_authors.insert(document_id, &pending);
+            self.env().emit_event(AuthorNominated {
+                id: document_id,
+                author,
+                by: *by,
+            });
+            Ok(())
+        }
+        fn remove_pending_author(
+            &mut self,
+            by: &AccountId,
+            document_id: DocumentId,
+            author: AccountId,
+        ) -> DocumentResult<()> {
+            let mut pending = self.document_pending_authors(document_id);
+            let Some(position) = pending.iter().position(|nominee| *nominee == author) else {
+                return Err(DocumentError::NotAuthors);
+            };
+            pending.swap_remove(position);
+            self.pending_authors.insert(document_id, &pending);
+            self.env().emit_event(AuthorRemoved {
+                id: document_id,
+                author,
+                by: *by,
+            });
+            Ok(())
+        }
+        fn confirm_author(&mut self, document_id: DocumentId, author: AccountId) {
+            let mut authors = self.document_authors(document_id);
+            authors.push(author);
+            self.document_authors.insert(document_id, &authors);
+            let count = self.document_authored_count(author);
+            self.authored_documents
+                .insert((author, count), &document_id);
+            self.authored_position.insert((author, document_id), &count);
+            self.authored_counter
+                .insert(author, &count.checked_add(1).expect("Overflow"));
+            self.env().emit_event(AuthorConfirmed {
+                id: document_id,
+                author,
+            });
+        }
+        // swap the last authored document of the author into the place of the removed one
+        fn unindex_authored(&mut self, author: &AccountId, document_id: DocumentId) {
+            let Some(position) = self.authored_position.take((author, document_id)) else {
+                return;
+            };
+            let last = self.document_authored_count(*author).saturating_sub(1);
+            if position != last {
+                if let Some(moved) = self.authored_documents.get((author, last)) {
+                    self.authored_documents.insert((author, position), &moved);
+                    self.authored_position.insert((author, moved), &position);
                 }
             }
+            self.authored_documents.remove((author, last));
+            self.authored_counter.insert(author, &last);
         }
-        // get the ipfs addr of the document
-        #[ink(message)]
-        pub fn document_location_get(&self, document_id: DocumentId) -> DocumentResult<IPFSaddr> {
-            match self.document_location.get(document_id) {
-                Some(ipfs_addr) => Ok(ipfs_addr),
-                None => Err(DocumentError::DocumentNotFound),
+        // the id can be minted again after a burn, the new document must not inherit the authors
+        fn clear_authors(&mut self, document_id: DocumentId) {
+            self.pending_authors.remove(document_id);
+            for author in self.document_authors.take(document_id).unwrap_or_default() {
+                self.unindex_authored(&author, document_id);
             }
         }
-        // verify the IPFS is stored to the corresponded document id
-        #[ink(message)]
-        pub fn verify_document_location(&self, document_id: DocumentId) -> DocumentResult<bool> {
-            match self.document_location.get(document_id) {
-                Some(_) => Ok(true),
-                None => Err(DocumentError::DocumentNotFound),
+        fn index_licensee(&mut self, licensee: &AccountId, document_id: DocumentId) {
+            let count = self.licensee_counter.get(document_id).unwrap_or(0);
+            self.licensees.insert((document_id, count), licensee);
+            self.licensee_counter
+                .insert(document_id, &count.checked_add(1).expect("Overflow"));
+        }
+        fn take_offer(&mut self, document_id: DocumentId, buyer: &AccountId) -> Option<Balance> {
+            let amount = self.offers.take((document_id, buyer))?;
+            if let Some(position) = self.offer_position.take((document_id, buyer)) {
+                let last = self
+                    .offer_counter
+                    .get(document_id)
+                    .unwrap_or(0)
+                    .saturating_sub(1);
+                if position != last {
+                    if let Some(moved) = self.offer_buyers.get((document_id, last)) {
+                        self.offer_buyers.insert((document_id, position), &moved);
+                        self.offer_position.insert((document_id, moved), &position);
+                    }
+                }
+                self.offer_buyers.remove((document_id, last));
+                self.offer_counter.insert(document_id, &last);
             }
+            Some(amount)
         }
-        //remove the ipfs location of the corresponding document
-        #[ink(message)]
-        pub fn document_location_delete(&mut self, document_id: DocumentId) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            if self.check_owner_owned_document(&caller, &document_id) == false {
-                return Err(DocumentError::NotOwner);
+        fn clear_offers(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            for index in 0..self.offer_counter.take(document_id).unwrap_or(0) {
+                let Some(buyer) = self.offer_buyers.take((document_id, index)) else {
+                    continue;
+                };
+                self.offer_position.remove((document_id, buyer));
+                if let Some(amount) = self.offers.take((document_id, buyer)) {
+                    self.pay(&buyer, amount)?;
+                }
             }
-            match self.document_location.get(document_id) {
-                Some(_) => {
-                    self.document_location.remove(document_id);
-                    self.env().emit_event(DocumentUpdated {
-                        action: UpdateMessage::ContentUpdate,
-                        from: caller,
-                        id: document_id,
-                    });
-                    Ok(())
+            Ok(())
+        }
+        // open proposals give their deposit back, settled ones are already gone
+        fn clear_proposals(&mut self, document_id: DocumentId) -> DocumentResult<()> {
+            for proposal_id in 0..self.proposal_counter.take(document_id).unwrap_or(0) {
+                if let Some(proposal) = self.proposals.take((document_id, proposal_id)) {
+                    self.pay(&proposal.proposer, proposal.deposit)?;
                 }
-                None => Err(DocumentError::NoDataFound),
             }
+            Ok(())
         }
-        //create a new document metadata
-        #[ink(message)]
-        pub fn document_metadata_new(
-            &mut self,
-            document_id: DocumentId,
-            metadata_hash: Hash,
-        ) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            if self.check_owner_owned_document(&caller, &document_id) {
-                return Err(DocumentError::NotOwner);
+        fn clear_attestations(&mut self, document_id: DocumentId) {
+            for index in 0..self.attestation_counter.take(document_id).unwrap_or(0) {
+                if let Some(attestation) = self.document_attestations.take((document_id, index)) {
+                    self.attested_signers
+                        .remove((document_id, &attestation.signer));
+                }
             }
-            match self.document_metadata.get(document_id) {
-                Some(_) => Err(DocumentError::DocumentIdAlreadyExists),
-                None => {
-                    self.document_metadata.insert(document_id, &metadata_hash);
-                    self.env().emit_event(DocumentUpdated {
-                        action: UpdateMessage::MetadataUpdate,
-                        from: caller,
-                        id: document_id,
-                    });
-                    Ok(())
+        }
+        fn clear_access_log(&mut self, document_id: DocumentId) {
+            for index in 0..self.access_log_len.take(document_id).unwrap_or(0) {
+                self.access_log.remove((document_id, index));
+            }
+        }
+        // the deliveries also leave the lists of their recipients
+        fn clear_deliveries(&mut self, document_id: DocumentId) {
+            for delivery_id in 0..self.delivery_counter.take(document_id).unwrap_or(0) {
+                let Some(delivery) = self.deliveries.take((document_id, delivery_id)) else {
+                    continue;
+                };
+                let recipient = delivery.recipient;
+                self.pending_deliveries.remove((document_id, recipient));
+                let Some(position) = self.received_position.take((document_id, delivery_id)) else {
+                    continue;
+                };
+                let last = self.received_count(recipient).saturating_sub(1);
+                if position != last {
+                    if let Some(moved) = self.received_deliveries.get((recipient, last)) {
+                        self.received_deliveries
+                            .insert((recipient, position), &moved);
+                        self.received_position.insert(moved, &position);
+                    }
                 }
+                self.received_deliveries.remove((recipient, last));
+                self.received_counter.insert(recipient, &last);
             }
         }
-        //get document metadata
-        #[ink(message)]
-        pub fn document_metadata_get(&self, document_id: DocumentId) -> DocumentResult<Hash> {
-            match self.document_metadata.get(document_id) {
-                Some(meta_hash) => Ok(meta_hash),
-                None => Err(DocumentError::DocumentNotFound),
+        // the template of a destroyed document cannot get new versions or documents
+        fn clear_template(&mut self, document_id: DocumentId) {
+            let Some(template_id) = self.backed_templates.take(document_id) else {
+                return;
+            };
+            if let Some(template) = self.templates.take(template_id) {
+                for version in 1..=template.latest_version {
+                    self.template_versions.remove((template_id, version));
+                }
             }
         }
-        //verify if the metadata stored is for the document
-        #[ink(message)]
-        pub fn verify_document_metadata(&self, document_id: DocumentId) -> DocumentResult<bool> {
-            match self.document_metadata.get(document_id) {
-                Some(_) => Ok(true),
-                None => Err(DocumentError::DocumentNotFound),
+        // a license is bought for one document, an id minted again after a burn is not covered
+        fn clear_licenses(&mut self, document_id: DocumentId) {
+            let count = self.licensee_counter.take(document_id).unwrap_or(0);
+            for index in 0..count {
+                if let Some(licensee) = self.licensees.take((document_id, index)) {
+                    self.licenses.remove((licensee, document_id));
+                }
             }
         }
-        //delete the document metadata from the storage
-        #[ink(message)]
-        pub fn document_metadata_delete(&mut self, document_id: DocumentId) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            if self.check_owner_owned_document(&caller, &document_id) == false {
-                return Err(DocumentError::NotOwner);
+        fn clear_key_envelopes(&mut self, document_id: DocumentId) {
+            for recipient in self
+                .envelope_recipients
+                .take(document_id)
+                .unwrap_or_default()
+            {
+                self.key_envelopes.remove((document_id, recipient));
             }
-            match self.document_metadata.get(document_id) {
-                Some(_) => {
-                    self.document_metadata.remove(document_id);
-                    self.env().emit_event(DocumentUpdated {
-                        action: UpdateMessage::MetadataUpdate,
-                        from: caller,
-                        id: document_id,
-                    });
-                    Ok(())
+            self.document_key_version.remove(document_id);
+        }
+        // the id can be minted again after a burn, the new document must not inherit the lineage
+        fn clear_links(&mut self, document_id: DocumentId) {
+            self.supersede_requests.remove(document_id);
+            self.superseded_by.remove(document_id);
+            for index in 0..self.outgoing_link_counter.take(document_id).unwrap_or(0) {
+                if let Some(link) = self.outgoing_links.take((document_id, index)) {
+                    self.unlink_incoming(&link);
+                }
+            }
+            for index in 0..self.incoming_link_counter.take(document_id).unwrap_or(0) {
+                if let Some(link) = self.incoming_links.take((document_id, index)) {
+                    self.unlink_outgoing(&link);
                 }
-                None => Err(DocumentError::NoDataFound),
             }
         }
-        //get the approved accountID for this documentID
-        #[ink(message)]
-        pub fn document_get_approve_acc(
-            &self,
-            document_id: DocumentId,
-        ) -> DocumentResult<AccountId> {
-            match self.document_approvals.get(document_id) {
-                Some(aproved_acc) => Ok(aproved_acc),
-                None => Err(DocumentError::AccountNotFound),
+        // remove the link from the incoming list of its target, its source is being cleared
+        fn unlink_incoming(&mut self, link: &DocumentLink) {
+            let Some(position) = self.linked.take((link.source, link.target, link.kind)) else {
+                return;
+            };
+            if link.kind == LinkKind::Supersedes {
+                self.superseded_by.remove(link.target);
             }
+            let last = self
+                .document_link_count_incoming(link.target)
+                .saturating_sub(1);
+            if position.incoming != last {
+                if let Some(moved) = self.incoming_links.get((link.target, last)) {
+                    self.incoming_links
+                        .insert((link.target, position.incoming), &moved);
+                    let moved_ref = (moved.source, moved.target, moved.kind);
+                    if let Some(mut moved_position) = self.linked.get(moved_ref) {
+                        moved_position.incoming = position.incoming;
+                        self.linked.insert(moved_ref, &moved_position);
+                    }
+                }
+            }
+            self.incoming_links.remove((link.target, last));
+            self.incoming_link_counter.insert(link.target, &last);
         }
-        //get number of owned documents of an accountId
-        #[ink(message)]
-        pub fn numof_owned_documents(&self, owner: AccountId) -> u32 {
-            self.owned_document_counter.get(owner).unwrap_or(0)
+        // remove the link from the outgoing list of its source, its target is being cleared
+        fn unlink_outgoing(&mut self, link: &DocumentLink) {
+            let Some(position) = self.linked.take((link.source, link.target, link.kind)) else {
+                return;
+            };
+            let last = self
+                .document_link_count_outgoing(link.source)
+                .saturating_sub(1);
+            if position.outgoing != last {
+                if let Some(moved) = self.outgoing_links.get((link.source, last)) {
+                    self.outgoing_links
+                        .insert((link.source, position.outgoing), &moved);
+                    let moved_ref = (moved.source, moved.target, moved.kind);
+                    if let Some(mut moved_position) = self.linked.get(moved_ref) {
+                        moved_position.outgoing = position.outgoing;
+                        self.linked.insert(moved_ref, &moved_position);
+                    }
+                }
+            }
+            self.outgoing_links.remove((link.source, last));
+            self.outgoing_link_counter.insert(link.source, &last);
         }
-        //transfer document to another account, only owner or approved account can do it
-        #[ink(message)]
-        pub fn tranfer_to(&mut self, to: AccountId, document_id: DocumentId) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            self.transfer_document_from(&caller, &to, document_id);
-            Ok(())
+        // `source` may supersede `target` when nothing superseded it yet and no cycle closes
+        fn ensure_supersedable(
+            &self,
+            source: DocumentId,
+            target: DocumentId,
+        ) -> DocumentResult<()> {
+            if self.superseded_by.contains(target) {
+                return Err(DocumentError::DuplicationData);
+            }
+            self.ensure_not_superseded_by(source, target)
         }
-        // transfer approved for owned toke
-        #[ink(message)]
-        pub fn transfer_from(
-            &mut self,
-            from: AccountId,
-            to: AccountId,
+        // walk up the supersedes chain of `document_id`, a link superseding `ancestor`
+        // would close a cycle when `ancestor` is found on it
+        fn ensure_not_superseded_by(
+            &self,
             document_id: DocumentId,
+            ancestor: DocumentId,
         ) -> DocumentResult<()> {
-            self.transfer_document_from(&from, &to, document_id)?;
-            Ok(())
+            let mut current = document_id;
+            for _ in 0..MAX_LINEAGE_DEPTH {
+                match self.superseded_by.get(current) {
+                    Some(newer) if newer == ancestor => return Err(DocumentError::LinkCycle),
+                    Some(newer) => current = newer,
+                    None => return Ok(()),
+                }
+            }
+            Err(DocumentError::LineageTooDeep)
         }
-        // approves the account to transfer the specific document on behalf of the caller
-        #[ink(message)]
-        pub fn approve(&mut self, to: AccountId, document_id: DocumentId) -> DocumentResult<()> {
-            self.approve_for(&to, document_id)?;
-            Ok(())
+        fn attested_content(&self, document_id: DocumentId) -> DocumentResult<Hash> {
+            self.record(document_id)?
+                .content
+                .ok_or(DocumentError::NoDataFound)
         }
-        // approve or disapprove the operator fro all documentId of the caller
-        #[ink(message)]
-        pub fn set_approval_for_all(
+        fn record_attestation(
             &mut self,
-            to: AccountId,
-            approved: bool,
+            document_id: DocumentId,
+            signer: SignerKey,
+            content_hash: Hash,
         ) -> DocumentResult<()> {
-            self.approve_for_all(to, approved)?;
-            Ok(())
-        }
-        // get the approved accountId for this documentID
-        #[ink(message)]
-        pub fn get_approved_account(&self, document_id: DocumentId) -> DocumentResult<AccountId> {
-            match self.document_approvals.get(document_id) {
-                Some(acc) => Ok(acc),
-                None => Err(DocumentError::NoDataFound),
+            if self.attested_signers.get((document_id, &signer)) == Some(content_hash) {
+                return Err(DocumentError::DuplicationData);
             }
+            let index = self.document_attestation_count(document_id);
+            let attestation = Attestation {
+                signer: signer.clone(),
+                content_hash,
+                submitter: self.env().caller(),
+                block: self.env().block_number(),
+            };
+            self.document_attestations
+                .insert((document_id, index), &attestation);
+            self.attestation_counter
+                .insert(document_id, &index.checked_add(1).expect("Overflow"));
+            self.attested_signers
+                .insert((document_id, &signer), &content_hash);
+            self.env().emit_event(DocumentAttested {
+                id: document_id,
+                submitter: attestation.submitter,
+                signer,
+                content_hash,
+            });
+            Ok(())
         }
-        // return to see if the operator is approve by the ownver
-        #[ink(message)]
-        pub fn is_approve_for_all(
-            &self,
-            owner: AccountId,
-            operator: AccountId,
-        ) -> DocumentResult<bool> {
-            Ok(self.approved_for_all(&owner, &operator))
-        }
-        #[ink(message)]
-        pub fn set_code_hash(&mut self, new_code_hash: Hash) {
-            self.env()
-                .set_code_hash(&new_code_hash)
-                .unwrap_or_else(|error| {
-                    panic!(
-                        "Failed to 'set_code_hash' to {:?} due to {:?}",
-                        new_code_hash, error
-                    )
-                });
-            ink::env::debug_print!("Switch to new code hash: {:?}", new_code_hash);
-        }
-
-        ///Helper function
         fn add_document_to(&mut self, to: &AccountId, id: DocumentId) -> DocumentResult<()> {
             if *to == AccountId::from([0x00; 32]) {
                 return Err(DocumentError::NotAllow);
             }
-            match self.document_owner.get(id) {
-                Some(_) => Err(DocumentError::DocumentIdAlreadyExists),
-                None => {
-                    self.document_owner.insert(id, to);
-                    Ok(())
-                }
-            }
-        }
-        fn check_owner_owned_document(&self, caller: &AccountId, document_id: &DocumentId) -> bool {
-            if let val = caller {
-                return *val
-                    == self
-                        .document_owner
-                        .get(&document_id)
-                        .expect("This caller oowned the document");
+            if self.documents.contains(id) {
+                return Err(DocumentError::DocumentIdAlreadyExists);
             }
-            false
-        }
-        fn check_is_proxy(&self, owner: AccountId, operator: AccountId) -> DocumentResult<bool> {
-            Ok(true)
+            let mut record = DocumentRecord {
+                owner: *to,
+                content: None,
+                metadata: None,
+                location: None,
+                approved: None,
+                deposit: StorageDeposit::default(),
+            };
+            self.charge_deposit(&mut record)?;
+            self.documents.insert(id, &record);
+            self.index_document(id);
+            Ok(())
         }
-
         fn increase_documents_count(&mut self, owner: &AccountId) {
             let count = self
                 .owned_document_counter
@@ -480,6 +4783,7 @@ pub mod document_management {
             Ok(())
         }
         fn approve_for_all(&mut self, to: AccountId, approved: bool) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::Approvals)?;
             let caller = self.env().caller();
             if to == caller {
                 return Err(DocumentError::NotAllow);
@@ -494,44 +4798,38 @@ pub mod document_management {
             }
             Ok(())
         }
-        fn remove_approve_account(&mut self, owner: AccountId, approved_acc: AccountId) {
-            if self.operator_approvals.get((owner, approved_acc)).unwrap() == false {
-                self.operator_approvals.remove((owner, approved_acc));
-            }
-        }
         fn approved_for_all(&self, owner: &AccountId, operator: &AccountId) -> bool {
             self.operator_approvals.contains((owner, operator))
         }
-        fn approve_for(&mut self, to: &AccountId, document_id: DocumentId) -> DocumentResult<()> {
-            let caller = self.env().caller();
-            let owner = self.document_owner_get(document_id).unwrap();
-            if !(self.check_owner_owned_document(&caller, &document_id)
-                || self.approved_for_all(&owner, &caller))
-            {
+        fn approve_for(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            document_id: DocumentId,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::Approvals)?;
+            let mut record = self.record(document_id)?;
+            if !(record.owner == *from || self.approved_for_all(&record.owner, from)) {
                 return Err(DocumentError::NotAllow);
             }
             if *to == AccountId::from([0x0; 32]) {
                 return Err(DocumentError::NotAllow);
             }
-            if self.document_approvals.contains(document_id) {
+            if record.approved.is_some() {
                 return Err(DocumentError::CannotInsert);
             } else {
-                self.document_approvals.insert(document_id, to);
+                self.charge_deposit(&mut record)?;
+                record.approved = Some(*to);
+                self.documents.insert(document_id, &record);
             }
 
             self.env().emit_event(Approval {
-                from: caller,
+                from: *from,
                 to: *to,
                 id: document_id,
             });
             Ok(())
         }
-        fn clear_approval(&mut self, document_id: DocumentId) -> DocumentResult<()> {
-            match self.document_approvals.get(document_id) {
-                Some(_) => Ok(self.document_approvals.remove(document_id)),
-                None => Err(DocumentError::NoDataFound),
-            }
-        }
         // check whether the 'from' accountid is owner or an approved account
         fn approved_or_owner(
             &self,
@@ -543,7 +4841,7 @@ pub mod document_management {
                 return Err(DocumentError::CannotInsert);
             }
             Ok(from == owner
-                || self.document_approvals.get(document_id) == Some(from)
+                || self.get_approved_account(document_id) == Ok(from)
                 || self.approved_for_all(&owner, &from))
         }
         fn transfer_document_from(
@@ -552,23 +4850,95 @@ pub mod document_management {
             to: &AccountId,
             document_id: DocumentId,
         ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::Transfers)?;
             let caller = self.env().caller();
-            let owner = self.document_owner_get(document_id).unwrap();
-            if !self.approved_or_owner(caller, owner, document_id).unwrap() {
+            let owner = self.document_owner_get(document_id)?;
+            if !self.approved_or_owner(caller, owner, document_id)? {
                 return Err(DocumentError::NotAllow);
             }
             if owner != *from {
                 return Err(DocumentError::NotOwner);
             }
-            self.clear_approval(document_id);
-            self.burn_document(document_id)?;
-            self.add_document_to(to, document_id);
-            self.env().emit_event(Transfer {
+            if self.listings.contains(document_id) {
+                return Err(DocumentError::DocumentIsListed);
+            }
+            if from != to {
+                self.ensure_within_quota(to)?;
+            }
+            self.move_document(from, to, document_id)
+        }
+        // swap the document and the payment, the seller is paid after the ownership moved
+        fn sell_document(
+            &mut self,
+            seller: &AccountId,
+            buyer: &AccountId,
+            document_id: DocumentId,
+            price: Balance,
+        ) -> DocumentResult<()> {
+            self.ensure_not_paused(PauseScope::Transfers)?;
+            self.ensure_within_quota(buyer)?;
+            self.move_document(seller, buyer, document_id)?;
+            self.pay_with_royalty(seller, document_id, price)?;
+            self.env().emit_event(DocumentSold {
+                id: document_id,
+                seller: *seller,
+                buyer: *buyer,
+                price,
+            });
+            Ok(())
+        }
+        // move the ownership, shared by the transfers and the sales
+        fn move_document(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            document_id: DocumentId,
+        ) -> DocumentResult<()> {
+            if *to == AccountId::from([0x0; 32]) {
+                return Err(DocumentError::NotAllow);
+            }
+            let mut record = self.record(document_id)?;
+            if record.approved.take().is_some() {
+                let payee = self.deposit_payee(from, document_id);
+                self.refund_deposit_entry(&mut record, &payee)?;
+            }
+            // leaving the custody of the contract account end the co-ownership
+            if *from == self.env().account_id() {
+                self.end_co_ownership(document_id);
+            }
+            // the envelopes got sealed by the previous owner, the new one distribute its own.
+            // Going into custody keep them, the creator is one of the co-owners
+            if *to != self.env().account_id() {
+                self.clear_key_envelopes(document_id);
+            }
+            record.owner = *to;
+            self.documents.insert(document_id, &record);
+            self.listings.remove(document_id);
+            self.clear_user(document_id);
+            self.decrease_documents_count(from)?;
+            self.increase_documents_count(to);
+            self.unindex_owned(from, document_id);
+            self.index_owned(to, document_id);
+            self.env().emit_event(OwnershipTransferred {
+                id: document_id,
                 from: Some(*from),
                 to: Some(*to),
-                id: document_id,
+                block: self.env().block_number(),
             });
             Ok(())
         }
     }
+
+    // royalty share of the price, split up so the multiplication cannot overflow
+    fn royalty_amount(price: Balance, basis_points: u16) -> Balance {
+        let denominator = Balance::from(ROYALTY_DENOMINATOR);
+        let basis_points = Balance::from(basis_points);
+        price / denominator * basis_points + price % denominator * basis_points / denominator
+    }
+
+    // clamp a paged query to the stored entries and the page size limit
+    fn page_range(count: u32, start: u32, limit: u32) -> core::ops::Range<u32> {
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+        start.min(end)..end
+    }
 }
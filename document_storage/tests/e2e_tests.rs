@@ -7,6 +7,7 @@
 mod e2e_tests {
     /// Imports all the definitions from the outer scope so we can use them here.
     use document_storage::document_management::{DocumentManagement, DocumentManagementRef, *};
+    use document_store_contract::document_acounts::{DocumentAcounts, DocumentAcountsRef};
 
     /// A helper function used for calling contract messages.
     use ink_e2e::{subxt::dynamic::Value, ChainBackend, ContractsBackend};
@@ -41,6 +42,106 @@ mod e2e_tests {
         Ok(())
     }
 
+    /// An account without an identity in the accounts contract can neither mint nor write
+    /// the content of a document once the access requirement is set.
+    #[ink_e2e::test]
+    async fn unregistered_account_is_rejected(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // Given
+        let mut accounts_constructor = DocumentAcountsRef::new();
+        let accounts = client
+            .instantiate(
+                "document_acounts",
+                &ink_e2e::alice(),
+                &mut accounts_constructor,
+            )
+            .submit()
+            .await
+            .expect("instantiate failed");
+        let mut accounts_builder = accounts.call_builder::<DocumentAcounts>();
+        client
+            .call(&ink_e2e::alice(), &accounts_builder.account_new())
+            .submit()
+            .await
+            .expect("account_new failed");
+        let mut constructor = DocumentManagementRef::new();
+        let contract = client
+            .instantiate("document_storage", &ink_e2e::alice(), &mut constructor)
+            .submit()
+            .await
+            .expect("instantiate failed");
+        let mut call_builder = contract.call_builder::<DocumentManagement>();
+        client
+            .call(
+                &ink_e2e::alice(),
+                &call_builder.set_access_requirement(Some(AccessRequirement {
+                    accounts: accounts.account_id,
+                    role: None,
+                })),
+            )
+            .submit()
+            .await
+            .expect("set_access_requirement failed");
+        // When
+        client
+            .call(&ink_e2e::alice(), &call_builder.document_new(1))
+            .submit()
+            .await
+            .expect("document_new failed");
+        let mint = client
+            .call(&ink_e2e::bob(), &call_builder.document_new(2))
+            .dry_run()
+            .await?;
+        let content = client
+            .call(
+                &ink_e2e::bob(),
+                &call_builder.document_content_new(1, [0x01; 32].into()),
+            )
+            .dry_run()
+            .await?;
+        let location = client
+            .call(
+                &ink_e2e::bob(),
+                &call_builder.document_addr_new(1, [0x02; 32].into()),
+            )
+            .dry_run()
+            .await?;
+        let metadata = client
+            .call(
+                &ink_e2e::bob(),
+                &call_builder.document_metadata_new(1, [0x03; 32].into()),
+            )
+            .dry_run()
+            .await?;
+        // Then
+        assert_eq!(
+            mint.return_value(),
+            Err(DocumentError::IdentityNotRegistered)
+        );
+        assert_eq!(
+            content.return_value(),
+            Err(DocumentError::IdentityNotRegistered)
+        );
+        assert_eq!(
+            location.return_value(),
+            Err(DocumentError::IdentityNotRegistered)
+        );
+        assert_eq!(
+            metadata.return_value(),
+            Err(DocumentError::IdentityNotRegistered)
+        );
+        // the registered owner still writes
+        let content = client
+            .call(
+                &ink_e2e::alice(),
+                &call_builder.document_content_new(1, [0x01; 32].into()),
+            )
+            .dry_run()
+            .await?;
+        assert_eq!(content.return_value(), Ok(()));
+
+        Ok(())
+    }
+
     /// We test that we can read and write a value from the on-chain contract.
     #[ink_e2e::test]
     async fn it_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
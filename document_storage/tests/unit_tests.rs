@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
-    use super::*;
     use document_storage::document_management::*;
+    use ink::primitives::{AccountId, Hash};
 
     #[ink::test]
     fn mint_works() {
@@ -64,7 +64,6 @@ mod tests {
     }
     #[ink::test]
     fn document_content_work() {
-        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
         let mut document = DocumentManagement::new();
         let content_hash: [u8; 32] = [0x00; 32];
         // ensure that document is created
@@ -77,4 +76,1539 @@ mod tests {
         // ensure that new content hash belong to the corresponded documentId
         assert_eq!(document.document_content_get(1), Some(content_hash.into()));
     }
+    #[ink::test]
+    fn attestation_rejects_invalid_signature() {
+        let mut document = DocumentManagement::new();
+        let content_hash: [u8; 32] = [0x01; 32];
+        assert_eq!(document.document_new(1), Ok(()));
+        // a document without content hash cannot be attested
+        assert_eq!(
+            document.attest_sr25519(1, [0x00; 64], [0x00; 32]),
+            Err(DocumentError::NoDataFound)
+        );
+        assert_eq!(
+            document.document_content_new(1, content_hash.into()),
+            Ok(())
+        );
+        // signatures that do not match the signer key are rejected
+        assert_eq!(
+            document.attest_sr25519(1, [0x00; 64], [0x00; 32]),
+            Err(DocumentError::InvalidSignature)
+        );
+        assert_eq!(
+            document.attest_ecdsa(1, [0x00; 65], [0x00; 33]),
+            Err(DocumentError::InvalidSignature)
+        );
+        // nothing get stored for a failed verification
+        assert_eq!(document.document_attestation_count(1), 0);
+        assert_eq!(document.document_attestations_get(1, 0, 10), vec![]);
+    }
+    #[ink::test]
+    fn attestation_accepts_signed_content_hash() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let content_hash: [u8; 32] = [0x01; 32];
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(
+            document.document_content_new(1, content_hash.into()),
+            Ok(())
+        );
+        // the signers sign the content hash off-chain, a relayer submits the signatures
+        let sr25519 = schnorrkel::MiniSecretKey::from_bytes(&[0x02; 32])
+            .unwrap()
+            .expand_to_keypair(schnorrkel::ExpansionMode::Ed25519);
+        let sr25519_signature = sr25519.sign_simple(b"substrate", &content_hash).to_bytes();
+        let sr25519_signer = sr25519.public.to_bytes();
+        let secret_key = secp256k1::SecretKey::from_slice(&[0x03; 32]).unwrap();
+        let (recovery_id, compact) = secp256k1::SECP256K1
+            .sign_ecdsa_recoverable(
+                &secp256k1::Message::from_digest_slice(&content_hash).unwrap(),
+                &secret_key,
+            )
+            .serialize_compact();
+        let mut ecdsa_signature = [0u8; 65];
+        ecdsa_signature[..64].copy_from_slice(&compact);
+        ecdsa_signature[64] = recovery_id.to_i32() as u8;
+        let ecdsa_signer = secret_key.public_key(secp256k1::SECP256K1).serialize();
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        // a signature from another key is rejected
+        assert_eq!(
+            document.attest_sr25519(1, sr25519_signature, [0x00; 32]),
+            Err(DocumentError::InvalidSignature)
+        );
+        assert_eq!(
+            document.attest_sr25519(1, sr25519_signature, sr25519_signer),
+            Ok(())
+        );
+        assert_eq!(
+            document.attest_ecdsa(1, ecdsa_signature, ecdsa_signer),
+            Ok(())
+        );
+        // the same signer attest the same content once
+        assert_eq!(
+            document.attest_ecdsa(1, ecdsa_signature, ecdsa_signer),
+            Err(DocumentError::DuplicationData)
+        );
+        assert_eq!(document.document_attestation_count(1), 2);
+        assert_eq!(
+            document.document_attestations_get(1, 0, 10),
+            vec![
+                Attestation {
+                    signer: SignerKey::Sr25519(sr25519_signer),
+                    content_hash: content_hash.into(),
+                    submitter: accounts.charlie,
+                    block: 0,
+                },
+                Attestation {
+                    signer: SignerKey::Ecdsa(ecdsa_signer),
+                    content_hash: content_hash.into(),
+                    submitter: accounts.charlie,
+                    block: 0,
+                },
+            ]
+        );
+    }
+    #[ink::test]
+    fn permit_checks_deadline_nonce_and_signature() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.permit_nonce(accounts.alice), 0);
+        // a relayer submits the permit on behalf of Alice
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        assert_eq!(
+            document.permit(accounts.alice, accounts.bob, 1, 10, 1, [0x00; 64]),
+            Err(DocumentError::InvalidNonce)
+        );
+        assert_eq!(
+            document.permit(accounts.bob, accounts.bob, 1, 10, 0, [0x00; 64]),
+            Err(DocumentError::NotOwner)
+        );
+        assert_eq!(
+            document.permit(accounts.alice, accounts.bob, 1, 10, 0, [0x00; 64]),
+            Err(DocumentError::InvalidSignature)
+        );
+        ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        assert_eq!(
+            document.permit(accounts.alice, accounts.bob, 1, 0, 0, [0x00; 64]),
+            Err(DocumentError::PermitExpired)
+        );
+        // rejected permits do not consume the nonce nor approve anything
+        assert_eq!(document.permit_nonce(accounts.alice), 0);
+        assert_eq!(
+            document.get_approved_account(1),
+            Err(DocumentError::NoDataFound)
+        );
+    }
+    #[ink::test]
+    fn permit_approves_with_owner_signature_once() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        // the account id of an sr25519 account is its public key
+        let keypair = schnorrkel::MiniSecretKey::from_bytes(&[0x04; 32])
+            .unwrap()
+            .expand_to_keypair(schnorrkel::ExpansionMode::Ed25519);
+        let owner = AccountId::from(keypair.public.to_bytes());
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.document_new(2), Ok(()));
+        let sign = |payload: Vec<u8>| keypair.sign_simple(b"substrate", &payload).to_bytes();
+        let signature = sign(document.permit_payload(owner, accounts.bob, 1, 10, 0));
+        // a relayer submits the permit on behalf of the owner
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        assert_eq!(
+            document.permit(owner, accounts.django, 1, 10, 0, signature),
+            Err(DocumentError::InvalidSignature)
+        );
+        assert_eq!(
+            document.permit(owner, accounts.bob, 1, 10, 0, signature),
+            Ok(())
+        );
+        assert_eq!(document.get_approved_account(1), Ok(accounts.bob));
+        assert_eq!(document.permit_nonce(owner), 1);
+        // the signed permit cannot be replayed
+        assert_eq!(
+            document.permit(owner, accounts.bob, 1, 10, 0, signature),
+            Err(DocumentError::InvalidNonce)
+        );
+        // the next permit is signed with the next nonce
+        assert_eq!(
+            document.permit(owner, accounts.bob, 2, 10, 1, signature),
+            Err(DocumentError::InvalidSignature)
+        );
+        let signature = sign(document.permit_payload(owner, accounts.bob, 2, 10, 1));
+        assert_eq!(
+            document.permit(owner, accounts.bob, 2, 10, 1, signature),
+            Ok(())
+        );
+        assert_eq!(document.get_approved_account(2), Ok(accounts.bob));
+        assert_eq!(document.permit_nonce(owner), 2);
+    }
+    #[ink::test]
+    fn license_purchase_works() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        // documents without terms cannot be licensed
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.purchase_license(1),
+            Err(DocumentError::NoDataFound)
+        );
+        // only the owner set the license terms
+        assert_eq!(
+            document.set_license_terms(1, 100, [0x02; 32].into(), Some(10)),
+            Err(DocumentError::NotOwner)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(
+            document.set_license_terms(1, 100, [0x02; 32].into(), Some(10)),
+            Ok(())
+        );
+        ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+            ink::env::test::callee::<ink::env::DefaultEnvironment>(),
+            1_000_000,
+        );
+        // Bob pay the license price to Alice
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert!(!document.has_license(accounts.bob, 1));
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(50);
+        assert_eq!(
+            document.purchase_license(1),
+            Err(DocumentError::IncorrectPayment)
+        );
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+        assert_eq!(document.purchase_license(1), Ok(()));
+        assert!(document.has_license(accounts.bob, 1));
+        assert!(document.has_license(accounts.alice, 1));
+        assert!(!document.has_license(accounts.charlie, 1));
+        // buying again extend the running license
+        assert_eq!(document.purchase_license(1), Ok(()));
+        assert_eq!(
+            document.license_get(accounts.bob, 1).unwrap().expires_at,
+            Some(20)
+        );
+        // the license lapse after its duration
+        for _ in 0..20 {
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        }
+        assert!(!document.has_license(accounts.bob, 1));
+        // a perpetual license is not bought twice
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(
+            document.set_license_terms(1, 100, [0x02; 32].into(), None),
+            Ok(())
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(document.purchase_license(1), Ok(()));
+        assert_eq!(
+            document.license_get(accounts.bob, 1).unwrap().expires_at,
+            None
+        );
+        assert_eq!(
+            document.purchase_license(1),
+            Err(DocumentError::DuplicationData)
+        );
+        // the license goes with the burned document, not with a new one minted on its id
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.burn_document(1), Ok(()));
+        assert_eq!(document.document_new(1), Ok(()));
+        assert!(!document.has_license(accounts.bob, 1));
+        assert_eq!(
+            document.license_get(accounts.bob, 1),
+            Err(DocumentError::NoDataFound)
+        );
+    }
+    #[ink::test]
+    fn listed_document_sale_works() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.approve(accounts.django, 1), Ok(()));
+        assert_eq!(document.list_document(1, 100), Ok(()));
+        // a listed document cannot be transferred directly
+        assert_eq!(
+            document.tranfer_to(accounts.charlie, 1),
+            Err(DocumentError::DocumentIsListed)
+        );
+        ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+            ink::env::test::callee::<ink::env::DefaultEnvironment>(),
+            1_000_000,
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(99);
+        assert_eq!(
+            document.buy_document(1),
+            Err(DocumentError::IncorrectPayment)
+        );
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+        assert_eq!(document.buy_document(1), Ok(()));
+        // ownership moved, listing and approval are cleared
+        assert_eq!(document.document_owner_get(1), Ok(accounts.bob));
+        assert_eq!(document.numof_owned_documents(accounts.alice), 0);
+        assert_eq!(document.numof_owned_documents(accounts.bob), 1);
+        assert_eq!(document.listing_get(1), Err(DocumentError::NotListed));
+        assert_eq!(
+            document.get_approved_account(1),
+            Err(DocumentError::NoDataFound)
+        );
+    }
+    #[ink::test]
+    fn offer_escrow_works() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+            ink::env::test::callee::<ink::env::DefaultEnvironment>(),
+            1_000_000,
+        );
+        // Bob and Charlie escrow offers for the document
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(50);
+        assert_eq!(document.make_offer(1), Ok(()));
+        assert_eq!(document.make_offer(1), Err(DocumentError::DuplicationData));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(70);
+        assert_eq!(document.make_offer(1), Ok(()));
+        assert_eq!(document.cancel_offer(1), Ok(()));
+        assert_eq!(
+            document.offer_get(1, accounts.charlie),
+            Err(DocumentError::OfferNotFound)
+        );
+        // only the owner accept an offer
+        assert_eq!(
+            document.accept_offer(1, accounts.bob),
+            Err(DocumentError::NotOwner)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.accept_offer(1, accounts.bob), Ok(()));
+        assert_eq!(document.document_owner_get(1), Ok(accounts.bob));
+        assert_eq!(
+            document.offer_get(1, accounts.bob),
+            Err(DocumentError::OfferNotFound)
+        );
+    }
+    #[ink::test]
+    fn sale_pays_royalty() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(
+            document.royalty_info(1, 100),
+            Err(DocumentError::DocumentNotFound)
+        );
+        assert_eq!(
+            document.document_new_with_royalty(1, accounts.charlie, 10_001),
+            Err(DocumentError::InvalidRoyalty)
+        );
+        // Charlie receive 10% of every sale
+        assert_eq!(
+            document.document_new_with_royalty(1, accounts.charlie, 1_000),
+            Ok(())
+        );
+        assert_eq!(document.royalty_info(1, 100), Ok((accounts.charlie, 10)));
+        assert_eq!(document.list_document(1, 100), Ok(()));
+        ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+            ink::env::test::callee::<ink::env::DefaultEnvironment>(),
+            1_000_000,
+        );
+        let charlie_balance =
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie)
+                .unwrap();
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+        assert_eq!(document.buy_document(1), Ok(()));
+        assert_eq!(
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie),
+            Ok(charlie_balance + 10)
+        );
+        // a license pays the owner the whole price
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+        assert_eq!(
+            document.set_license_terms(1, 100, [0x02; 32].into(), None),
+            Ok(())
+        );
+        let bob_balance =
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                .unwrap();
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+        assert_eq!(document.purchase_license(1), Ok(()));
+        assert_eq!(
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob),
+            Ok(bob_balance + 100)
+        );
+        assert_eq!(
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie),
+            Ok(charlie_balance + 10)
+        );
+        // a document without royalty owes nothing to its owner
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+        assert_eq!(document.document_new(2), Ok(()));
+        assert_eq!(document.royalty_info(2, 100), Ok((accounts.bob, 0)));
+    }
+    #[ink::test]
+    fn rental_user_lapses_and_clears_on_transfer() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.user_of(1), None);
+        // Bob rent the document for 5 blocks
+        assert_eq!(document.set_user(1, accounts.bob, 5), Ok(()));
+        assert_eq!(document.user_of(1), Some(accounts.bob));
+        assert_eq!(document.user_expires(1), 5);
+        assert!(document.has_license(accounts.bob, 1));
+        for _ in 0..5 {
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        }
+        assert_eq!(document.user_of(1), None);
+        assert!(!document.has_license(accounts.bob, 1));
+        // the rental does not survive a transfer
+        assert_eq!(document.set_user(1, accounts.bob, 20), Ok(()));
+        assert_eq!(document.tranfer_to(accounts.charlie, 1), Ok(()));
+        assert_eq!(document.user_of(1), None);
+        assert_eq!(document.user_expires(1), 0);
+    }
+    #[ink::test]
+    fn key_envelopes_distribution_and_rotation() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(
+            document.key_envelope_add(1, accounts.bob, vec![0x01; 48]),
+            Ok(())
+        );
+        assert_eq!(
+            document.key_envelope_add(1, accounts.charlie, vec![0x02; 48]),
+            Ok(())
+        );
+        assert_eq!(
+            document.key_envelope_add(1, accounts.django, vec![0x03; 1024]),
+            Err(DocumentError::EnvelopeTooLarge)
+        );
+        assert_eq!(document.key_envelope_revoke(1, accounts.charlie), Ok(()));
+        assert_eq!(document.key_envelope_recipients(1), vec![accounts.bob]);
+        // rotation has to re-key exactly the remaining recipients
+        assert_eq!(
+            document.key_envelopes_rotate(1, vec![(accounts.charlie, vec![0x04; 48])]),
+            Err(DocumentError::EnvelopeMismatch)
+        );
+        assert_eq!(
+            document.key_envelopes_rotate(1, vec![(accounts.bob, vec![0x05; 48])]),
+            Ok(())
+        );
+        // recipients fetch their own envelope
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.key_envelope_get(1),
+            Ok(KeyEnvelope {
+                key_version: 1,
+                data: vec![0x05; 48],
+            })
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        assert_eq!(
+            document.key_envelope_get(1),
+            Err(DocumentError::NoDataFound)
+        );
+        // the envelopes of the previous owner do not survive a transfer
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.tranfer_to(accounts.django, 1), Ok(()));
+        assert_eq!(document.key_envelope_recipients(1), Vec::<AccountId>::new());
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.key_envelope_get(1),
+            Err(DocumentError::NoDataFound)
+        );
+    }
+    #[ink::test]
+    fn supersedes_links_reject_cycles() {
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.document_new(2), Ok(()));
+        assert_eq!(document.document_new(3), Ok(()));
+        // 2 supersedes 1, 3 supersedes 2
+        assert_eq!(
+            document.document_link_new(2, 1, LinkKind::Supersedes),
+            Ok(())
+        );
+        assert_eq!(
+            document.document_link_new(3, 2, LinkKind::Supersedes),
+            Ok(())
+        );
+        assert_eq!(document.document_superseded_by(1), Some(2));
+        assert_eq!(document.document_info(2).unwrap().superseded_by, Some(3));
+        // 1 superseding 3 would close the chain
+        assert_eq!(
+            document.document_link_new(1, 3, LinkKind::Supersedes),
+            Err(DocumentError::LinkCycle)
+        );
+        // other kinds of links are not part of the supersedes chain
+        assert_eq!(
+            document.document_link_new(1, 3, LinkKind::References),
+            Ok(())
+        );
+        assert_eq!(
+            document.document_links_incoming(3, 0, 10),
+            vec![DocumentLink {
+                source: 1,
+                target: 3,
+                kind: LinkKind::References,
+            }]
+        );
+        assert_eq!(document.document_links_outgoing(3, 0, 10).len(), 1);
+        // superseding the document of another owner waits for that owner
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        assert_eq!(document.document_new(5), Ok(()));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(document.document_new(6), Ok(()));
+        assert_eq!(document.document_new(7), Ok(()));
+        assert_eq!(
+            document.document_link_new(6, 5, LinkKind::Supersedes),
+            Ok(())
+        );
+        assert_eq!(document.document_superseded_by(5), None);
+        assert_eq!(document.document_supersede_request_get(6), Some(5));
+        assert_eq!(
+            document.document_supersede_accept(6),
+            Err(DocumentError::NotOwner)
+        );
+        assert_eq!(
+            document.document_link_new(7, 5, LinkKind::Supersedes),
+            Ok(())
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.document_supersede_reject(7), Ok(()));
+        assert_eq!(document.document_supersede_request_get(7), None);
+        assert_eq!(document.document_supersede_accept(6), Ok(()));
+        assert_eq!(document.document_superseded_by(5), Some(6));
+        assert_eq!(document.document_supersede_request_get(6), None);
+        // a burned document takes its links along, a new document on its id has no lineage
+        assert_eq!(
+            document.document_link_new(2, 3, LinkKind::References),
+            Ok(())
+        );
+        assert_eq!(document.document_link_new(1, 3, LinkKind::Amends), Ok(()));
+        assert_eq!(document.burn_document(2), Ok(()));
+        assert_eq!(document.document_superseded_by(1), None);
+        assert_eq!(document.document_links_outgoing(3, 0, 10), vec![]);
+        assert_eq!(
+            document.document_links_incoming(3, 0, 10),
+            vec![
+                DocumentLink {
+                    source: 1,
+                    target: 3,
+                    kind: LinkKind::References,
+                },
+                DocumentLink {
+                    source: 1,
+                    target: 3,
+                    kind: LinkKind::Amends,
+                },
+            ]
+        );
+        assert_eq!(document.document_new(2), Ok(()));
+        assert_eq!(document.document_info(2).unwrap().superseded_by, None);
+        assert_eq!(document.document_links_incoming(2, 0, 10), vec![]);
+        assert_eq!(
+            document.document_link_new(1, 3, LinkKind::Amends),
+            Err(DocumentError::DuplicationData)
+        );
+        assert_eq!(document.burn_document(1), Ok(()));
+        assert_eq!(document.document_links_incoming(3, 0, 10), vec![]);
+    }
+    #[ink::test]
+    fn retention_blocks_burn_and_allows_purge() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let policy: [u8; 32] = [0x07; 32];
+        assert_eq!(document.admin_get(), Some(accounts.alice));
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.document_content_new(1, [0x01; 32].into()), Ok(()));
+        // only the admin define and assign policies
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.retention_policy_new(policy.into(), 2, Some(4)),
+            Err(DocumentError::NotAdmin)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(
+            document.retention_policy_new(policy.into(), 2, Some(1)),
+            Err(DocumentError::InvalidRetention)
+        );
+        assert_eq!(
+            document.retention_policy_new(policy.into(), 2, Some(4)),
+            Ok(())
+        );
+        assert_eq!(document.document_retention_assign(1, policy.into()), Ok(()));
+        assert_eq!(
+            document.burn_document(1),
+            Err(DocumentError::RetentionActive)
+        );
+        // the retained data cannot be wiped without burning the document either
+        assert_eq!(document.document_metadata_new(1, [0x02; 32].into()), Ok(()));
+        assert_eq!(document.document_addr_new(1, [0x03; 32].into()), Ok(()));
+        assert_eq!(
+            document.remove_document_content(1),
+            Err(DocumentError::RetentionActive)
+        );
+        assert_eq!(
+            document.document_metadata_delete(1),
+            Err(DocumentError::RetentionActive)
+        );
+        assert_eq!(
+            document.document_location_delete(1),
+            Err(DocumentError::RetentionActive)
+        );
+        assert_eq!(document.purge_expired(1), Err(DocumentError::NotExpired));
+        // what refer to the document goes with it
+        assert_eq!(document.document_new(2), Ok(()));
+        assert_eq!(
+            document.document_link_new(1, 2, LinkKind::References),
+            Ok(())
+        );
+        assert_eq!(
+            document.template_register(1, [0x01; 32].into(), [0x04; 32].into()),
+            Ok(0)
+        );
+        assert_eq!(
+            document.template_register(1, [0x01; 32].into(), [0x04; 32].into()),
+            Err(DocumentError::DuplicationData)
+        );
+        assert_eq!(document.mark_delivered(1, accounts.django, None), Ok(0));
+        ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+            ink::env::test::callee::<ink::env::DefaultEnvironment>(),
+            1_000_000,
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(50);
+        assert_eq!(document.make_offer(1), Ok(()));
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+        let charlie_balance =
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie)
+                .unwrap();
+        for _ in 0..4 {
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        }
+        // anyone purge the expired document
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(document.purge_expired(1), Ok(()));
+        assert_eq!(
+            document.document_owner_get(1),
+            Err(DocumentError::DocumentNotFound)
+        );
+        assert_eq!(document.document_content_get(1), None);
+        assert_eq!(document.numof_owned_documents(accounts.alice), 1);
+        assert_eq!(document.document_links_incoming(2, 0, 10), vec![]);
+        assert_eq!(document.template_get(0), None);
+        assert_eq!(document.template_version_get(0, 1), None);
+        assert_eq!(document.delivery_count(1), 0);
+        assert_eq!(document.deliveries_to(accounts.django, 0, 10), vec![]);
+        assert_eq!(document.received_count(accounts.django), 0);
+        // the escrowed offer is refunded
+        assert_eq!(
+            document.offer_get(1, accounts.charlie),
+            Err(DocumentError::OfferNotFound)
+        );
+        assert_eq!(
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie),
+            Ok(charlie_balance + 50)
+        );
+    }
+    #[ink::test]
+    fn storage_deposit_charged_and_refunded() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.set_deposit_per_entry(10), Ok(()));
+        ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+            ink::env::test::callee::<ink::env::DefaultEnvironment>(),
+            1_000_000,
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.document_new(1),
+            Err(DocumentError::IncorrectPayment)
+        );
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.document_content_new(1, [0x01; 32].into()), Ok(()));
+        assert_eq!(document.document_deposit_get(1), 20);
+        assert_eq!(document.document_info(1).unwrap().deposit, 20);
+        // removing an entry refund its deposit to the owner
+        let bob_balance =
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                .unwrap();
+        assert_eq!(document.remove_document_content(1), Ok(()));
+        assert_eq!(document.document_deposit_get(1), 10);
+        assert_eq!(
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob),
+            Ok(bob_balance + 10)
+        );
+        assert_eq!(document.burn_document(1), Ok(()));
+        assert_eq!(document.document_deposit_get(1), 0);
+        assert_eq!(
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob),
+            Ok(bob_balance + 20)
+        );
+    }
+    #[ink::test]
+    fn quota_limits_mint_and_transfer() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.set_default_quota(Some(1)), Ok(()));
+        assert_eq!(document.set_account_quota(accounts.alice, Some(2)), Ok(()));
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.document_new(2), Ok(()));
+        assert_eq!(document.document_new(3), Err(DocumentError::QuotaExceeded));
+        assert_eq!(
+            document.document_quota_get(accounts.alice),
+            QuotaUsage {
+                used: 2,
+                limit: Some(2),
+            }
+        );
+        // Bob fall back to the default quota
+        assert_eq!(document.tranfer_to(accounts.bob, 1), Ok(()));
+        assert_eq!(
+            document.tranfer_to(accounts.bob, 2),
+            Err(DocumentError::QuotaExceeded)
+        );
+        assert_eq!(document.set_account_quota(accounts.alice, None), Ok(()));
+        assert_eq!(document.document_quota_get(accounts.alice).limit, Some(1));
+    }
+    #[ink::test]
+    fn pause_stops_operations() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        // only the admin switch the circuit breakers
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.pause(PauseScope::Approvals),
+            Err(DocumentError::NotAdmin)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.pause(PauseScope::Approvals), Ok(()));
+        assert_eq!(
+            document.approve(accounts.bob, 1),
+            Err(DocumentError::OperationPaused)
+        );
+        assert_eq!(
+            document.set_approval_for_all(accounts.bob, true),
+            Err(DocumentError::OperationPaused)
+        );
+        // other categories keep working
+        assert_eq!(document.document_new(2), Ok(()));
+        // the global pause stop every category
+        assert_eq!(document.pause(PauseScope::All), Ok(()));
+        assert!(document.is_paused(PauseScope::Minting));
+        assert_eq!(
+            document.document_new(3),
+            Err(DocumentError::OperationPaused)
+        );
+        assert_eq!(document.unpause(PauseScope::All), Ok(()));
+        assert_eq!(document.unpause(PauseScope::Approvals), Ok(()));
+        assert_eq!(document.approve(accounts.bob, 1), Ok(()));
+    }
+    #[ink::test]
+    fn upgrade_is_time_locked() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let code_hash: [u8; 32] = [0x09; 32];
+        assert_eq!(document.upgrade_authority_get(), Some(accounts.alice));
+        // a shorter delay waits for the current one to pass
+        assert_eq!(document.set_upgrade_delay(2), Ok(()));
+        assert_eq!(document.upgrade_delay_get(), 14_400);
+        assert_eq!(
+            document.pending_upgrade_delay_get(),
+            Some(PendingDelay {
+                delay: 2,
+                effective_at: 14_400,
+            })
+        );
+        for _ in 0..14_400 {
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        }
+        assert_eq!(document.upgrade_delay_get(), 2);
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.propose_upgrade(code_hash.into()),
+            Err(DocumentError::NotUpgradeAuthority)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.propose_upgrade(code_hash.into()), Ok(()));
+        assert_eq!(
+            document.pending_upgrade_get(),
+            Some(PendingUpgrade {
+                code_hash: code_hash.into(),
+                executable_at: 14_402,
+            })
+        );
+        // the proposal cannot be applied before the delay passed
+        assert_eq!(
+            document.execute_upgrade(),
+            Err(DocumentError::UpgradeNotReady)
+        );
+        assert_eq!(document.set_upgrade_delay(0), Err(DocumentError::NotAllow));
+        assert_eq!(document.cancel_upgrade(), Ok(()));
+        assert_eq!(document.pending_upgrade_get(), None);
+        assert_eq!(document.execute_upgrade(), Err(DocumentError::NoDataFound));
+        // a longer delay applies at once
+        assert_eq!(document.set_upgrade_delay(10), Ok(()));
+        assert_eq!(document.upgrade_delay_get(), 10);
+        assert_eq!(document.pending_upgrade_delay_get(), None);
+    }
+    #[ink::test]
+    fn migration_runs_in_chunks_and_blocks_writes() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.layout_version_get(), 4);
+        for id in 1..=60 {
+            assert_eq!(document.document_new(id), Ok(()));
+        }
+        assert_eq!(document.burn_document(1), Ok(()));
+        assert_eq!(document.document_total(), 59);
+        assert_eq!(document.document_by_index(0), Some(60));
+        // already on the current version
+        assert_eq!(document.migrate(0, 1), Ok(true));
+        assert_eq!(
+            document.migrate(1, 3),
+            Err(DocumentError::WrongLayoutVersion)
+        );
+        // an instance deployed before the layout got versioned has no version stored
+        ink::env::clear_contract_storage(&0xbff53dd4u32);
+        assert_eq!(document.layout_version_get(), 0);
+        assert_eq!(
+            document.document_new(61),
+            Err(DocumentError::MigrationInProgress)
+        );
+        assert_eq!(
+            document.cancel_listing(2),
+            Err(DocumentError::MigrationInProgress)
+        );
+        assert_eq!(
+            document.recovery_veto(),
+            Err(DocumentError::MigrationInProgress)
+        );
+        assert_eq!(document.document_index_backfill(vec![2, 3, 61]), Ok(0));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(document.migrate(0, 1), Err(DocumentError::NotAdmin));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        // 59 documents take two chunks
+        assert_eq!(document.migrate(0, 1), Ok(false));
+        assert_eq!(
+            document.migration_get(),
+            Some(MigrationState {
+                from: 0,
+                to: 1,
+                cursor: 50,
+            })
+        );
+        assert_eq!(
+            document.document_new(61),
+            Err(DocumentError::MigrationInProgress)
+        );
+        assert_eq!(document.migrate(0, 1), Ok(true));
+        assert_eq!(document.migration_get(), None);
+        assert_eq!(document.layout_version_get(), 1);
+        assert_eq!(document.migrate(0, 1), Ok(true));
+        assert_eq!(
+            document.document_new(61),
+            Err(DocumentError::MigrationInProgress)
+        );
+        assert_eq!(document.migrate(1, 2), Ok(false));
+        assert_eq!(document.migrate(1, 2), Ok(true));
+        assert_eq!(document.migrate(2, 3), Ok(false));
+        assert_eq!(document.migrate(2, 3), Ok(true));
+        assert_eq!(document.migrate(3, 4), Ok(false));
+        assert_eq!(document.migrate(3, 4), Ok(true));
+        assert_eq!(document.document_new(61), Ok(()));
+    }
+    #[ink::test]
+    fn migrations_move_legacy_document_fields() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let content: Hash = [0x07; 32].into();
+        assert_eq!(document.document_new(1), Ok(()));
+        // rewrite the document the way version 1 stored it, the owner in its own mapping
+        // and the content under the owned document counter key
+        ink::env::set_contract_storage(&0xbff53dd4u32, &1u32);
+        ink::env::clear_contract_storage(&(0xe4f2b1b1u32, 1u32));
+        ink::env::set_contract_storage(&(0xba8adf68u32, 1u32), &accounts.alice);
+        ink::env::set_contract_storage(&(0x2434e302u32, 1u32), &content);
+        assert_eq!(document.document_content_get(1), None);
+        assert_eq!(document.migrate(1, 2), Ok(true));
+        assert_eq!(
+            ink::env::get_contract_storage::<_, Hash>(&(0x2434e302u32, 1u32)),
+            Ok(None)
+        );
+        assert_eq!(document.migrate(2, 3), Ok(true));
+        assert_eq!(document.document_owner_get(1), Ok(accounts.alice));
+        assert_eq!(document.document_content_get(1), Some(content));
+        assert_eq!(
+            ink::env::get_contract_storage::<_, AccountId>(&(0xba8adf68u32, 1u32)),
+            Ok(None)
+        );
+        // the owned document counter is left untouched
+        assert_eq!(document.numof_owned_documents(accounts.alice), 1);
+        // version 3 had no owner index
+        ink::env::clear_contract_storage(&(0x8f71afb4u32, (accounts.alice, 0u32)));
+        ink::env::clear_contract_storage(&(0xfd6c93a9u32, 1u32));
+        ink::env::clear_contract_storage(&(0x5e24a82eu32, accounts.alice));
+        assert_eq!(
+            document.documents_owned_by(accounts.alice, 0, 10),
+            Vec::<DocumentId>::new()
+        );
+        assert_eq!(document.migrate(3, 4), Ok(true));
+        assert_eq!(document.documents_owned_by(accounts.alice, 0, 10), vec![1]);
+    }
+    #[ink::test]
+    fn access_requirement_is_admin_only() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let requirement = AccessRequirement {
+            accounts: accounts.django,
+            role: Some(Role::Speculator),
+        };
+        // no requirement configured, every account can mint
+        assert_eq!(document.access_requirement_get(), None);
+        assert_eq!(document.document_new(1), Ok(()));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.set_access_requirement(Some(requirement.clone())),
+            Err(DocumentError::NotAdmin)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(
+            document.set_access_requirement(Some(requirement.clone())),
+            Ok(())
+        );
+        assert_eq!(document.access_requirement_get(), Some(requirement));
+        assert_eq!(document.set_access_requirement(None), Ok(()));
+        assert_eq!(document.access_requirement_get(), None);
+    }
+    #[ink::test]
+    fn document_owned_by_works() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        assert!(document.document_owned_by(accounts.alice, 1));
+        assert!(!document.document_owned_by(accounts.bob, 1));
+        assert!(!document.document_owned_by(accounts.alice, 2));
+    }
+    #[ink::test]
+    fn document_events_carry_values() {
+        let mut document = DocumentManagement::new();
+        let content: Hash = [0x05; 32].into();
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.document_content_new(1, content), Ok(()));
+        assert_eq!(document.burn_document(1), Ok(()));
+        let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+        // OwnershipTransferred, ContentAnchored and Burned
+        assert_eq!(events.len(), 3);
+        let signature: Hash = [
+            0x29, 0x41, 0x5a, 0x95, 0x0d, 0x90, 0xe3, 0xfa, 0x12, 0x18, 0x21, 0x56, 0xc5, 0xb9,
+            0xe4, 0x29, 0xc8, 0x89, 0xfe, 0x97, 0xdb, 0x4b, 0x89, 0x0b, 0x23, 0x23, 0xcd, 0x31,
+            0x15, 0x35, 0x45, 0xd2,
+        ]
+        .into();
+        assert_eq!(events[1].topics[0], signature.as_ref().to_vec());
+        // the content hash is a topic, indexers can look the document up by its content
+        assert_eq!(events[1].topics[3], content.as_ref().to_vec());
+    }
+    #[ink::test]
+    fn document_record_reduces_storage_access() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+        let storage_rw =
+            || ink::env::test::get_contract_storage_rw::<ink::env::DefaultEnvironment>(&contract);
+        let content: Hash = [0x01; 32].into();
+        assert_eq!(document.document_new(1), Ok(()));
+        // rewrite the document the way version 2 stored it, every field in its own mapping
+        ink::env::set_contract_storage(&0xbff53dd4u32, &2u32);
+        ink::env::clear_contract_storage(&(0xe4f2b1b1u32, 1u32));
+        ink::env::set_contract_storage(&(0xba8adf68u32, 1u32), &accounts.alice);
+        ink::env::set_contract_storage(&(0x9da24437u32, 1u32), &content);
+        // version 2 `document_info` read the owner, content, metadata, location and deposit
+        // mappings, then the superseded link
+        let (reads, _) = storage_rw();
+        for key in [0xba8adf68u32, 0x9da24437, 0x934025b6, 0x9474161a] {
+            let _ = ink::env::get_contract_storage::<_, [u8; 32]>(&(key, 1u32));
+        }
+        let _ = ink::env::get_contract_storage::<_, StorageDeposit>(&(0xa5919537u32, 1u32));
+        let _ = ink::env::get_contract_storage::<_, DocumentId>(&(0xdffcc1afu32, 1u32));
+        let legacy_info_reads = storage_rw().0 - reads;
+        // version 2 `document_addr_new` wrote the location and the deposit apart
+        let (_, writes) = storage_rw();
+        ink::env::set_contract_storage(&(0x9474161au32, 1u32), &[0x02u8; 32]);
+        ink::env::set_contract_storage(&(0xa5919537u32, 1u32), &StorageDeposit::default());
+        let legacy_addr_writes = storage_rw().1 - writes;
+        ink::env::clear_contract_storage(&(0x9474161au32, 1u32));
+        ink::env::clear_contract_storage(&(0xa5919537u32, 1u32));
+        assert_eq!(document.migrate(2, 3), Ok(true));
+        assert_eq!(document.migrate(3, 4), Ok(true));
+        assert_eq!(document.document_content_get(1), Some(content));
+        // the same messages on the record read the record and the superseded link
+        let (reads, writes) = storage_rw();
+        assert!(document.document_info(1).is_ok());
+        let (info_reads, info_writes) = storage_rw();
+        assert_eq!((info_reads - reads, info_writes), (2, writes));
+        assert_eq!(legacy_info_reads, 6);
+        // and write the location along with the deposit
+        let (_, writes) = storage_rw();
+        assert_eq!(document.document_addr_new(1, [0x02; 32].into()), Ok(()));
+        assert_eq!(storage_rw().1 - writes, 1);
+        assert_eq!(legacy_addr_writes, 2);
+        // a single field cost one read of the record
+        let (reads, _) = storage_rw();
+        assert_eq!(document.document_location_get(1), Ok([0x02; 32].into()));
+        assert_eq!(storage_rw().0, reads + 1);
+    }
+    #[ink::test]
+    fn authors_need_consent_and_survive_transfer() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        assert_eq!(
+            document.document_new_with_authors(1, vec![accounts.alice, accounts.bob]),
+            Ok(())
+        );
+        // the owner is named right away, bob has to accept first
+        assert_eq!(document.document_authors(1), vec![accounts.alice]);
+        assert_eq!(document.document_pending_authors(1), vec![accounts.bob]);
+        assert_eq!(
+            document.author_nominate(1, accounts.bob),
+            Err(DocumentError::DuplicationData)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        assert_eq!(
+            document.author_nominate(1, accounts.charlie),
+            Err(DocumentError::NotPublisher)
+        );
+        assert_eq!(document.author_accept(1), Err(DocumentError::NotAuthors));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(document.author_accept(1), Ok(()));
+        assert_eq!(
+            document.document_authors(1),
+            vec![accounts.alice, accounts.bob]
+        );
+        assert_eq!(document.documents_authored_by(accounts.bob, 0, 10), vec![1]);
+        // the credit stay with the document after it changes hands
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.tranfer_to(accounts.charlie, 1), Ok(()));
+        assert_eq!(
+            document.document_authors(1),
+            vec![accounts.alice, accounts.bob]
+        );
+        assert_eq!(
+            document.author_nominate(1, accounts.django),
+            Err(DocumentError::NotPublisher)
+        );
+        assert_eq!(document.author_resign(1), Ok(()));
+        assert_eq!(document.document_authors(1), vec![accounts.bob]);
+        assert_eq!(document.document_authored_count(accounts.alice), 0);
+    }
+    #[ink::test]
+    fn co_owned_document_needs_quorum() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        // the custody account must differ from every co-owner
+        ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([0x07; 32]));
+        let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let owners = vec![accounts.alice, accounts.bob, accounts.charlie];
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(
+            document.set_license_terms(1, 100, [0x02; 32].into(), None),
+            Ok(())
+        );
+        assert_eq!(
+            document.co_ownership_create(1, owners.clone(), 4),
+            Err(DocumentError::InvalidThreshold)
+        );
+        assert_eq!(document.co_ownership_create(1, owners, 2), Ok(()));
+        // the custody account cannot be paid for licenses, the terms are gone
+        assert_eq!(
+            document.license_terms_get(1),
+            Err(DocumentError::NoDataFound)
+        );
+        // the contract account hold the document, the co-owners act through proposals
+        assert_eq!(document.document_owner_get(1), Ok(contract));
+        assert!(document.document_owned_by(contract, 1));
+        assert!(!document.document_owned_by(accounts.bob, 1));
+        assert!(document.is_co_owner(accounts.bob, 1));
+        assert!(!document.is_co_owner(accounts.django, 1));
+        assert!(document.has_license(accounts.bob, 1));
+        assert_eq!(document.documents_owned_by(contract, 0, 10), vec![1]);
+        assert_eq!(
+            document.tranfer_to(accounts.django, 1),
+            Err(DocumentError::NotAllow)
+        );
+        let transfer = CoOwnerAction::Transfer {
+            to: accounts.django,
+        };
+        assert_eq!(document.set_deposit_per_entry(10), Ok(()));
+        ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract, 1_000_000);
+        // a stored proposal cost the deposit of one entry, none while paused
+        assert_eq!(
+            document.proposal_new(1, transfer.clone(), 10),
+            Err(DocumentError::IncorrectPayment)
+        );
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+        assert_eq!(document.pause(PauseScope::All), Ok(()));
+        assert_eq!(
+            document.proposal_new(1, transfer.clone(), 10),
+            Err(DocumentError::OperationPaused)
+        );
+        assert_eq!(document.unpause(PauseScope::All), Ok(()));
+        assert_eq!(document.proposal_new(1, transfer.clone(), 10), Ok(0));
+        assert_eq!(document.proposal_get(1, 0).unwrap().deposit, 10);
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+        assert_eq!(document.proposal_get(1, 0).unwrap().confirmations.len(), 1);
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+        assert_eq!(
+            document.proposal_confirm(1, 0),
+            Err(DocumentError::NotCoOwner)
+        );
+        // a revoked confirmation no longer counts
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.proposal_revoke(1, 0), Ok(()));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(document.proposal_confirm(1, 0), Ok(()));
+        assert_eq!(document.document_owner_get(1), Ok(contract));
+        // expired proposals cannot be confirmed anymore
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+        assert_eq!(document.proposal_new(1, CoOwnerAction::Burn, 0), Ok(1));
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+        ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        assert_eq!(
+            document.proposal_confirm(1, 1),
+            Err(DocumentError::ProposalExpired)
+        );
+        // the executed proposal refund its proposer
+        let balance = |account| {
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap()
+        };
+        let alice_balance = balance(accounts.alice);
+        assert_eq!(document.proposal_confirm(1, 0), Ok(()));
+        assert_eq!(balance(accounts.alice), alice_balance + 10);
+        assert_eq!(document.document_owner_get(1), Ok(accounts.django));
+        assert_eq!(document.co_ownership_get(1), None);
+        assert_eq!(document.proposal_get(1, 0), Err(DocumentError::NoDataFound));
+        // the leftover proposal is cancelled by its proposer only
+        assert_eq!(document.proposal_cancel(1, 1), Err(DocumentError::NotAllow));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        let bob_balance = balance(accounts.bob);
+        assert_eq!(document.proposal_cancel(1, 1), Ok(()));
+        assert_eq!(balance(accounts.bob), bob_balance + 10);
+    }
+    #[ink::test]
+    fn guardians_recover_documents_of_lost_account() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        // the custody account must differ from every co-owner
+        ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([0x07; 32]));
+        let mut document = DocumentManagement::new();
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.document_new(2), Ok(()));
+        assert_eq!(
+            document.documents_owned_by(accounts.alice, 0, 10),
+            vec![1, 2]
+        );
+        assert_eq!(document.document_new(3), Ok(()));
+        assert_eq!(
+            document.co_ownership_create(3, vec![accounts.alice, accounts.frank], 2),
+            Ok(())
+        );
+        assert_eq!(document.document_new(4), Ok(()));
+        assert_eq!(
+            document.co_ownership_create(4, vec![accounts.alice, accounts.eve], 2),
+            Ok(())
+        );
+        assert_eq!(document.set_deposit_per_entry(10), Ok(()));
+        ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+            ink::env::test::callee::<ink::env::DefaultEnvironment>(),
+            1_000_000,
+        );
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+        assert_eq!(document.approve(accounts.django, 1), Ok(()));
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+        let guardians = vec![accounts.bob, accounts.charlie];
+        assert_eq!(
+            document.recovery_config_set(guardians.clone(), 3, 5),
+            Err(DocumentError::InvalidThreshold)
+        );
+        assert_eq!(document.recovery_config_set(guardians, 2, 5), Ok(()));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+        assert_eq!(
+            document.recovery_initiate(accounts.alice, accounts.eve),
+            Err(DocumentError::NotGuardian)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.recovery_initiate(accounts.alice, accounts.eve),
+            Ok(())
+        );
+        assert_eq!(
+            document.recovery_finalize(accounts.alice),
+            Err(DocumentError::RecoveryNotReady)
+        );
+        // the owner still holding its key veto the recovery
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.recovery_veto(), Ok(()));
+        assert_eq!(document.recovery_get(accounts.alice), None);
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.recovery_initiate(accounts.alice, accounts.eve),
+            Ok(())
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        assert_eq!(document.recovery_approve(accounts.alice), Ok(()));
+        assert_eq!(
+            document.recovery_approve(accounts.alice),
+            Err(DocumentError::DuplicationData)
+        );
+        assert_eq!(
+            document.recovery_get(accounts.alice).unwrap().executable_at,
+            Some(5)
+        );
+        assert_eq!(
+            document.recovery_finalize(accounts.alice),
+            Err(DocumentError::RecoveryNotReady)
+        );
+        for _ in 0..5 {
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        }
+        // the approval share of the deposit is refunded to the new account, anyone finalize
+        let eve_balance =
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve)
+                .unwrap();
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+        assert_eq!(document.recovery_finalize(accounts.alice), Ok(true));
+        assert_eq!(
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve),
+            Ok(eve_balance + 5)
+        );
+        assert_eq!(
+            document.get_approved_account(1),
+            Err(DocumentError::NoDataFound)
+        );
+        // the co-owned documents stay in custody, the lost account is replaced in their owners
+        let co_ownership = document.co_ownership_get(3).unwrap();
+        assert_eq!(co_ownership.owners, vec![accounts.eve, accounts.frank]);
+        assert_eq!(co_ownership.creator, accounts.eve);
+        assert!(!document.is_co_owner(accounts.alice, 3));
+        // a co-ownership the new account already had is not counted twice
+        let co_ownership = document.co_ownership_get(4).unwrap();
+        assert_eq!(co_ownership.owners, vec![accounts.eve]);
+        assert_eq!(co_ownership.threshold, 1);
+        assert_eq!(document.document_owner_get(1), Ok(accounts.eve));
+        assert_eq!(document.document_owner_get(2), Ok(accounts.eve));
+        assert_eq!(document.numof_owned_documents(accounts.alice), 0);
+        assert_eq!(
+            document.documents_owned_by(accounts.alice, 0, 10),
+            Vec::<DocumentId>::new()
+        );
+        assert_eq!(document.documents_owned_by(accounts.eve, 0, 10), vec![2, 1]);
+        assert_eq!(document.recovery_get(accounts.alice), None);
+    }
+    #[ink::test]
+    fn audited_access_is_recorded() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+        let location: IPFSaddr = [0x02; 32].into();
+        let envelope = vec![0x01; 48];
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.document_addr_new(1, location), Ok(()));
+        assert_eq!(
+            document.key_envelope_add(1, accounts.bob, envelope.clone()),
+            Ok(())
+        );
+        assert_eq!(
+            document.key_envelope_add(1, accounts.charlie, envelope.clone()),
+            Ok(())
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.document_audited_access_set(1, true),
+            Err(DocumentError::NotOwner)
+        );
+        // a published location has to be removed first, it is then only sealed in the envelopes
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(
+            document.document_audited_access_set(1, true),
+            Err(DocumentError::NotAllow)
+        );
+        assert_eq!(document.document_location_delete(1), Ok(()));
+        assert_eq!(document.document_audited_access_set(1, true), Ok(()));
+        assert_eq!(
+            document.document_addr_new(1, location),
+            Err(DocumentError::AuditedAccessRequired)
+        );
+        assert_eq!(document.document_info(1).unwrap().location, None);
+        // the free getters are refused, checking the key envelope cost the audited flag read
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.document_location_get(1),
+            Err(DocumentError::AuditedAccessRequired)
+        );
+        let (reads, _) =
+            ink::env::test::get_contract_storage_rw::<ink::env::DefaultEnvironment>(&contract);
+        assert_eq!(
+            document.key_envelope_get(1),
+            Err(DocumentError::AuditedAccessRequired)
+        );
+        assert_eq!(
+            ink::env::test::get_contract_storage_rw::<ink::env::DefaultEnvironment>(&contract).0,
+            reads + 1
+        );
+        // the owner has no envelope sealed for itself
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(
+            document.document_access(1, 7),
+            Err(DocumentError::NoDataFound)
+        );
+        assert_eq!(document.set_deposit_per_entry(10), Ok(()));
+        // an envelope is enough, no license is needed. Every record cost the deposit of one
+        // entry and the call only return its position in the log, never the envelope
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.document_access(1, 7),
+            Err(DocumentError::IncorrectPayment)
+        );
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+        assert_eq!(document.document_access(1, 7), Ok(0));
+        ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        assert_eq!(document.document_access(1, 9), Ok(1));
+        assert_eq!(
+            document.key_envelope_get(1),
+            Err(DocumentError::AuditedAccessRequired)
+        );
+        assert_eq!(
+            document.document_access_log(1, 0, 10),
+            Err(DocumentError::NotOwner)
+        );
+        // the access is refused while the contract is paused
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.pause(PauseScope::All), Ok(()));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.document_access(1, 9),
+            Err(DocumentError::OperationPaused)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.unpause(PauseScope::All), Ok(()));
+        assert_eq!(document.document_access_count(1), 2);
+        assert_eq!(
+            document.document_access_log(1, 1, 10),
+            Ok(vec![AccessRecord {
+                accessor: accounts.bob,
+                purpose: 9,
+                block: 1,
+            }])
+        );
+        assert_eq!(document.document_audited_access_set(1, false), Ok(()));
+        assert_eq!(document.document_addr_new(1, location), Ok(()));
+        assert_eq!(document.document_location_get(1), Ok(location));
+    }
+    #[ink::test]
+    fn deliveries_are_acknowledged_or_expire() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let revision: Hash = [0x01; 32].into();
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(
+            document.mark_delivered(1, accounts.bob, None),
+            Err(DocumentError::NoDataFound)
+        );
+        assert_eq!(document.document_content_new(1, revision), Ok(()));
+        assert_eq!(document.mark_delivered(1, accounts.bob, Some(5)), Ok(0));
+        assert_eq!(
+            document.mark_delivered(1, accounts.bob, None),
+            Err(DocumentError::DuplicationData)
+        );
+        assert_eq!(document.mark_delivered(1, accounts.charlie, Some(1)), Ok(1));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.acknowledge(1, [0x02; 32].into()),
+            Err(DocumentError::WrongRevision)
+        );
+        assert_eq!(document.acknowledge(1, revision), Ok(()));
+        assert_eq!(
+            document.delivery_get(1, 0).unwrap().status,
+            DeliveryStatus::Acknowledged
+        );
+        // charlie missed the deadline
+        ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        assert_eq!(
+            document.acknowledge(1, revision),
+            Err(DocumentError::DeadlinePassed)
+        );
+        assert_eq!(
+            document.delivery_get(1, 1).unwrap().status,
+            DeliveryStatus::Expired
+        );
+        assert_eq!(document.delivery_expire(1, accounts.charlie), Ok(()));
+        assert_eq!(document.delivery_get(1, 1).unwrap().settled_at, Some(2));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.mark_delivered(1, accounts.charlie, None), Ok(2));
+        assert_eq!(document.document_deliveries(1, 0, 10).len(), 3);
+        let received = document.deliveries_to(accounts.charlie, 0, 10);
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[1].2.status, DeliveryStatus::Pending);
+        // without a deadline only the sender can withdraw the pending delivery
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+        assert_eq!(
+            document.delivery_cancel(1, accounts.charlie),
+            Err(DocumentError::NotAllow)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.delivery_cancel(1, accounts.charlie), Ok(()));
+        assert_eq!(
+            document.delivery_get(1, 2).unwrap().status,
+            DeliveryStatus::Cancelled
+        );
+        assert_eq!(
+            document.delivery_cancel(1, accounts.charlie),
+            Err(DocumentError::NoDataFound)
+        );
+        // a delivery cost the deposit of one entry, none while paused
+        assert_eq!(document.set_deposit_per_entry(10), Ok(()));
+        assert_eq!(
+            document.mark_delivered(1, accounts.charlie, None),
+            Err(DocumentError::IncorrectPayment)
+        );
+        ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+        assert_eq!(document.pause(PauseScope::All), Ok(()));
+        assert_eq!(
+            document.mark_delivered(1, accounts.charlie, None),
+            Err(DocumentError::OperationPaused)
+        );
+        assert_eq!(document.unpause(PauseScope::All), Ok(()));
+        assert_eq!(document.mark_delivered(1, accounts.charlie, None), Ok(3));
+    }
+    #[ink::test]
+    fn documents_are_instantiated_from_templates() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let schema: Hash = [0x0a; 32].into();
+        let fields: Hash = [0x0f; 32].into();
+        let first_id: DocumentId = 0;
+        assert_eq!(document.document_new(1), Ok(()));
+        assert_eq!(document.document_new(2), Ok(()));
+        // every id can be picked by the callers
+        assert_eq!(document.document_new(1 << 31), Ok(()));
+        assert_eq!(
+            document.template_register(1, [0x01; 32].into(), schema),
+            Err(DocumentError::WrongRevision)
+        );
+        assert_eq!(document.document_content_new(1, [0x01; 32].into()), Ok(()));
+        assert_eq!(
+            document.template_register(1, [0x01; 32].into(), schema),
+            Ok(0)
+        );
+        // the contract allocate the first id no document took yet
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(document.document_from_template(0, fields), Ok(first_id));
+        assert_eq!(document.document_owner_get(first_id), Ok(accounts.bob));
+        assert_eq!(
+            document.document_template_get(first_id),
+            Some(TemplateUse {
+                template_id: 0,
+                version: 1,
+                field_values: fields,
+            })
+        );
+        assert_eq!(
+            document.document_links_outgoing(first_id, 0, 10),
+            vec![DocumentLink {
+                source: first_id,
+                target: 1,
+                kind: LinkKind::DerivedFrom,
+            }]
+        );
+        assert_eq!(
+            document.template_version_deprecate(0, 1),
+            Err(DocumentError::NotOwner)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.template_version_deprecate(0, 1), Ok(()));
+        assert_eq!(
+            document.document_from_template(0, fields),
+            Err(DocumentError::TemplateDeprecated)
+        );
+        assert_eq!(
+            document.template_version_new(0, [0x02; 32].into(), schema),
+            Err(DocumentError::WrongRevision)
+        );
+        assert_eq!(document.remove_document_content(1), Ok(()));
+        assert_eq!(document.document_content_new(1, [0x02; 32].into()), Ok(()));
+        assert_eq!(
+            document.template_version_new(0, [0x02; 32].into(), schema),
+            Ok(2)
+        );
+        // the ids picked by the callers are skipped
+        assert_eq!(document.document_from_template(0, fields), Ok(3));
+        assert_eq!(document.document_template_get(3).unwrap().version, 2);
+        // documents made from the deprecated version keep it
+        assert_eq!(document.document_template_get(first_id).unwrap().version, 1);
+    }
+    #[ink::test]
+    fn legacy_instance_claims_admin_and_migrates() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let content: Hash = [0x07; 32].into();
+        // the storage of an instance deployed before the admin and the layout version
+        // existed: no admin, no upgrade authority, the owner and the content in their
+        // own mappings and the content under the owned document counter prefix
+        for key in [0x792892c5u32, 0x53e34186, 0xfed431df, 0xbff53dd4] {
+            ink::env::clear_contract_storage(&key);
+        }
+        ink::env::set_contract_storage(&(0xba8adf68u32, 1u32), &accounts.bob);
+        ink::env::set_contract_storage(&(0x2434e302u32, 1u32), &content);
+        ink::env::set_contract_storage(&(0x2434e302u32, accounts.bob), &1u32);
+        assert_eq!(document.admin_get(), None);
+        assert_eq!(document.layout_version_get(), 0);
+        assert_eq!(document.migrate(0, 1), Err(DocumentError::NotAdmin));
+        // the account that upgraded the code claims the admin once
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        assert_eq!(document.claim_admin(), Ok(()));
+        assert_eq!(document.upgrade_authority_get(), Some(accounts.charlie));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+        assert_eq!(document.claim_admin(), Err(DocumentError::NotAllow));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        assert_eq!(document.document_index_backfill(vec![1]), Ok(1));
+        for (from, to) in [(0, 1), (1, 2), (2, 3), (3, 4)] {
+            assert_eq!(document.migrate(from, to), Ok(true));
+        }
+        assert_eq!(document.document_owner_get(1), Ok(accounts.bob));
+        assert_eq!(document.document_content_get(1), Some(content));
+        assert_eq!(document.documents_owned_by(accounts.bob, 0, 10), vec![1]);
+        // writes are open again
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(document.document_new(2), Ok(()));
+        assert_eq!(document.numof_owned_documents(accounts.bob), 2);
+    }
+    #[ink::test]
+    fn metadata_new_requires_owner() {
+        let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+        let mut document = DocumentManagement::new();
+        let metadata: Hash = [0x03; 32].into();
+        assert_eq!(
+            document.document_metadata_new(1, metadata),
+            Err(DocumentError::DocumentNotFound)
+        );
+        assert_eq!(document.document_new(1), Ok(()));
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+        assert_eq!(
+            document.document_metadata_new(1, metadata),
+            Err(DocumentError::NotOwner)
+        );
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        assert_eq!(document.document_metadata_new(1, metadata), Ok(()));
+        assert_eq!(document.document_metadata_get(1), Ok(metadata));
+    }
 }